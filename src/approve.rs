@@ -0,0 +1,349 @@
+use std::path::Path;
+
+use globset::GlobSet;
+
+use crate::{
+    config::{ApproveOptions, CompareConfig},
+    errors::ivc::IVCError,
+    logger::Logger,
+    models::{BaselineChange, PairResult, PairStatus, ReviewDecision, RunResults},
+    utils::{
+        file_paths::{get_file_location, get_relative_key},
+        file_system::{
+            baselines::{copy_file_to_location, remove_file_at_location},
+            history::create_snapshot,
+            results::{read_run_results, write_run_outputs},
+        },
+        globs::create_glob_set,
+    },
+};
+
+/// Promotes the mismatched and new latest images from the last run's results to be the new
+/// original images, optionally removing originals whose latest image has been deleted.
+///
+/// The current originals are first archived to a snapshot in the history directory, so that the
+/// approval can be undone via `rollback`. The approval is then recorded in the results file and
+/// html report, so that approving again does not repeat it. Returns every change that was made to
+/// the original images, each of which is also logged.
+pub fn approve(
+    config: impl Into<CompareConfig>,
+    options: &ApproveOptions,
+) -> Result<Vec<BaselineChange>, IVCError> {
+    let config = config.into();
+    let mut run_results = read_run_results(&config.get_results_file_location())?;
+    let glob_set = create_glob_set(&options.only)?;
+
+    let baseline_changes = get_baseline_changes(&config, &run_results, &glob_set, options);
+
+    if baseline_changes.is_empty() {
+        Logger::info("Approval complete - no baselines to change".to_string());
+        return Ok(baseline_changes);
+    }
+
+    apply_baseline_changes(&config, &baseline_changes)?;
+    record_approval(&mut run_results, &baseline_changes);
+    write_run_outputs(&config, &run_results)?;
+
+    Logger::info(format!(
+        "Approval complete - number of baseline changes: '{}'",
+        baseline_changes.len()
+    ));
+
+    Ok(baseline_changes)
+}
+
+/// Archives the current version of each affected original to a snapshot, then makes every change
+/// to the original images, logging each.
+pub fn apply_baseline_changes(
+    config: &CompareConfig,
+    baseline_changes: &[BaselineChange],
+) -> Result<(), IVCError> {
+    let originals: Vec<String> = baseline_changes
+        .iter()
+        .map(|baseline_change| baseline_change.get_original().to_owned())
+        .collect();
+    let snapshot = create_snapshot(
+        &config.get_history_dir(),
+        &config.get_original_images_dir(),
+        &originals,
+    )?;
+    Logger::info(format!(
+        "Archived current baselines to snapshot: '{}'",
+        snapshot.id
+    ));
+
+    for baseline_change in baseline_changes.iter() {
+        apply_baseline_change(baseline_change)?;
+        Logger::info(format!("Approved: {baseline_change}"));
+    }
+
+    Ok(())
+}
+
+/// Returns the changes approving would make to the original images, without making them.
+///
+/// Pairs that already have a review decision are skipped, as they were approved or rejected via
+/// the review server.
+pub fn get_baseline_changes(
+    config: &CompareConfig,
+    run_results: &RunResults,
+    glob_set: &GlobSet,
+    options: &ApproveOptions,
+) -> Vec<BaselineChange> {
+    let original_dir = config.get_original_images_dir();
+    let latest_dir = config.get_latest_images_dir();
+
+    let is_selected =
+        |relative_key: &str| options.only.is_empty() || glob_set.is_match(relative_key);
+    let get_original_location = |relative_key: &str| {
+        Path::new(&original_dir)
+            .join(relative_key)
+            .to_string_lossy()
+            .to_string()
+    };
+
+    let mut baseline_changes = Vec::new();
+
+    for pair in run_results
+        .get_mismatched_pairs()
+        .filter(|pair| pair.review.is_none())
+    {
+        let latest_file_location = get_file_location(&pair.latest);
+        let relative_key = get_relative_key(latest_file_location, &latest_dir)
+            .unwrap_or(latest_file_location.to_owned());
+        if is_selected(&relative_key) {
+            for baseline_change in get_pair_baseline_changes(config, pair) {
+                // every mismatched page of a multi-page image changes the same file
+                if !baseline_changes.contains(&baseline_change) {
+                    baseline_changes.push(baseline_change);
+                }
+            }
+        }
+    }
+
+    for new_image in run_results.new_images.iter() {
+        let relative_key = get_relative_key(new_image, &latest_dir).unwrap_or(new_image.to_owned());
+        if is_selected(&relative_key) {
+            baseline_changes.push(BaselineChange::Added {
+                latest: new_image.to_owned(),
+                original: get_original_location(&relative_key),
+            });
+        }
+    }
+
+    if options.remove_deleted {
+        for missing_image in run_results.missing_images.iter() {
+            let relative_key =
+                get_relative_key(missing_image, &original_dir).unwrap_or(missing_image.to_owned());
+            if is_selected(&relative_key) {
+                baseline_changes.push(BaselineChange::Removed {
+                    original: missing_image.to_owned(),
+                });
+            }
+        }
+    }
+
+    baseline_changes
+}
+
+/// Returns the changes approving a single mismatched pair would make to the original images.
+///
+/// The latest image replaces the original at the same relative path, removing the paired original
+/// when it is at a different path, such as when paired across formats. For a page of a multi-page
+/// image the whole file is replaced.
+pub fn get_pair_baseline_changes(config: &CompareConfig, pair: &PairResult) -> Vec<BaselineChange> {
+    let latest_dir = config.get_latest_images_dir();
+    let latest_file_location = get_file_location(&pair.latest);
+    let original_file_location = get_file_location(&pair.original);
+    let relative_key = get_relative_key(latest_file_location, &latest_dir)
+        .unwrap_or(latest_file_location.to_owned());
+    let original_location = Path::new(&config.get_original_images_dir())
+        .join(relative_key)
+        .to_string_lossy()
+        .to_string();
+
+    let mut baseline_changes = Vec::new();
+
+    if original_location != original_file_location {
+        baseline_changes.push(BaselineChange::Removed {
+            original: original_file_location.to_owned(),
+        });
+    }
+    baseline_changes.push(BaselineChange::Updated {
+        latest: latest_file_location.to_owned(),
+        original: original_location,
+    });
+
+    baseline_changes
+}
+
+/// Marks the approved pairs as reviewed, and stops listing the approved new images and removed
+/// missing images, as each now matches the original images.
+fn record_approval(run_results: &mut RunResults, baseline_changes: &[BaselineChange]) {
+    let is_approved_latest = |location: &str| {
+        baseline_changes
+            .iter()
+            .any(|baseline_change| match baseline_change {
+                BaselineChange::Updated { latest, .. } | BaselineChange::Added { latest, .. } => {
+                    latest == location
+                }
+                BaselineChange::Removed { .. } => false,
+            })
+    };
+
+    for pair in run_results.pairs.iter_mut().filter(|pair| {
+        pair.status == PairStatus::Mismatched
+            && pair.review.is_none()
+            && is_approved_latest(get_file_location(&pair.latest))
+    }) {
+        pair.review = Some(ReviewDecision::Approved);
+    }
+
+    run_results
+        .new_images
+        .retain(|new_image| !is_approved_latest(new_image));
+    run_results.missing_images.retain(|missing_image| {
+        !baseline_changes.contains(&BaselineChange::Removed {
+            original: missing_image.to_owned(),
+        })
+    });
+}
+
+fn apply_baseline_change(baseline_change: &BaselineChange) -> Result<(), IVCError> {
+    match baseline_change {
+        BaselineChange::Updated { latest, original }
+        | BaselineChange::Added { latest, original } => copy_file_to_location(latest, original),
+        BaselineChange::Removed { original } => remove_file_at_location(original),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod approve {
+        use std::{fs, path::Path};
+
+        use crate::{
+            approve::approve,
+            config::ApproveOptions,
+            models::{BaselineChange, ReviewDecision},
+            test_utils::results::setup_last_run,
+            utils::file_system::results::{read_run_results, write_run_results},
+        };
+
+        #[test]
+        fn updates_mismatched_and_adds_new_originals() {
+            let (temp_dir_handler, config) = setup_last_run();
+
+            let result = approve(config, &ApproveOptions::default()).unwrap();
+
+            let original = |name: &str| {
+                temp_dir_handler.get_location_of_file_name(&format!("original/{name}"))
+            };
+            let latest =
+                |name: &str| temp_dir_handler.get_location_of_file_name(&format!("latest/{name}"));
+            assert_eq!(
+                vec![
+                    BaselineChange::Updated {
+                        latest: latest("checkout/cart.png"),
+                        original: original("checkout/cart.png"),
+                    },
+                    BaselineChange::Updated {
+                        latest: latest("home.png"),
+                        original: original("home.png"),
+                    },
+                    BaselineChange::Added {
+                        latest: latest("new.png"),
+                        original: original("new.png"),
+                    },
+                ],
+                result
+            );
+            assert_eq!("latest", fs::read_to_string(original("home.png")).unwrap());
+            assert_eq!("latest", fs::read_to_string(original("new.png")).unwrap());
+            assert!(Path::new(&original("gone.png")).exists());
+        }
+
+        #[test]
+        fn only_approves_images_matching_glob_and_removes_deleted_when_requested() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let options = ApproveOptions {
+                only: vec!["checkout/**".to_string(), "gone.*".to_string()],
+                remove_deleted: true,
+            };
+
+            let result = approve(config, &options).unwrap();
+
+            let original = |name: &str| {
+                temp_dir_handler.get_location_of_file_name(&format!("original/{name}"))
+            };
+            assert_eq!(2, result.len());
+            assert_eq!(
+                "latest",
+                fs::read_to_string(original("checkout/cart.png")).unwrap()
+            );
+            assert_eq!(
+                "original",
+                fs::read_to_string(original("home.png")).unwrap()
+            );
+            assert!(!Path::new(&original("new.png")).exists());
+            assert!(!Path::new(&original("gone.png")).exists());
+        }
+
+        #[test]
+        fn skips_pairs_already_reviewed() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let results_location = config.get_results_file_location();
+            let mut run_results = read_run_results(&results_location).unwrap();
+            run_results.pairs[1].review = Some(ReviewDecision::Rejected);
+            write_run_results(&results_location, &run_results).unwrap();
+
+            let result = approve(config, &ApproveOptions::default()).unwrap();
+
+            assert_eq!(2, result.len());
+            assert_eq!(
+                "original",
+                fs::read_to_string(temp_dir_handler.get_location_of_file_name("original/home.png"))
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn records_the_approval_so_approving_again_changes_nothing() {
+            let (_temp_dir_handler, config) = setup_last_run();
+            let options = ApproveOptions {
+                remove_deleted: true,
+                ..ApproveOptions::default()
+            };
+
+            approve(config.clone(), &options).unwrap();
+            let history_dir = config.get_history_dir();
+            let snapshot_count = fs::read_dir(&history_dir).unwrap().count();
+            let result = approve(config.clone(), &options).unwrap();
+
+            let run_results = read_run_results(&config.get_results_file_location()).unwrap();
+            assert!(result.is_empty());
+            assert_eq!(snapshot_count, fs::read_dir(&history_dir).unwrap().count());
+            assert!(run_results
+                .get_mismatched_pairs()
+                .all(|pair| pair.review == Some(ReviewDecision::Approved)));
+            assert!(run_results.new_images.is_empty());
+            assert!(run_results.missing_images.is_empty());
+            assert!(Path::new(&config.get_report_file_location()).exists());
+        }
+
+        #[test]
+        fn returns_error_when_there_are_no_results() {
+            let (temp_dir_handler, config) = setup_last_run();
+            fs::remove_file(config.get_results_file_location()).unwrap();
+
+            let result = approve(config, &ApproveOptions::default());
+
+            assert!(result.is_err());
+            assert_eq!(
+                "original",
+                fs::read_to_string(temp_dir_handler.get_location_of_file_name("original/home.png"))
+                    .unwrap()
+            );
+        }
+    }
+}
@@ -1,39 +1,403 @@
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView};
 
 use crate::{
-    models::{ImageHolder, PixelCoord},
-    utils::image::is_pixel_for_images_matching,
+    errors::{handling::create_dimension_mismatch_error, ivc::IVCError},
+    logger::Logger,
+    models::{
+        AnimationComparison, AnimationResult, CompareOptions, ComparisonOutcome, ComparisonResult,
+        DiffLayout, FrameResult, ImageHolder, MismatchRegion, PixelCoord, RegionCrop,
+    },
+    utils::{
+        colour_profile::handle_colour_profiles,
+        file_paths::{
+            get_animated_mismatched_image_location, get_blink_location,
+            get_formatted_mismatched_image_location, get_region_crop_location,
+        },
+        file_system::images::{
+            get_pair_of_images_from_file_locations, save_animation_to_file_location,
+            save_image_to_file_location,
+        },
+        image::{
+            are_dimensions_matching_for_images, create_blink_frames, create_crop_thumbnail,
+            create_mismatch_frames, create_mismatch_image, get_crop_regions, get_mismatch_regions,
+            is_comparing_at_native_precision, is_native_pixel_for_images_matching,
+            is_pixel_for_images_matching,
+        },
+    },
 };
 
+/// Compares a single original and latest image file, logging how many pixels are mismatched.
+///
+/// When the images are mismatched and a diff location is passed, the mismatch image is written to
+/// it, with its extension replaced by the diff format's, otherwise no files are written. Svg
+/// images are rasterised with the options' svg options.
+pub fn compare_image_files(
+    original_location: &str,
+    latest_location: &str,
+    diff_location: Option<&str>,
+    options: &CompareOptions,
+) -> Result<ComparisonResult, IVCError> {
+    let image_pair = get_pair_of_images_from_file_locations(
+        original_location,
+        latest_location,
+        options.get_svg(),
+    )?;
+
+    compare_image_pair(image_pair, diff_location, options)
+}
+
+/// Compares a pair of images already read from their files, as `compare_image_files` does, such as
+/// a single page of a pair of multi-page images.
+pub fn compare_image_pair(
+    image_pair: (ImageHolder, ImageHolder),
+    diff_location: Option<&str>,
+    options: &CompareOptions,
+) -> Result<ComparisonResult, IVCError> {
+    if !are_dimensions_matching_for_images(&image_pair) {
+        return Err(create_dimension_mismatch_error(image_pair));
+    }
+
+    let (image_pair, profiles) = handle_colour_profiles(image_pair, options.get_profiles());
+    let (mismatched_pixels, animation_comparison) =
+        compare_pair_of_images_and_frames(&image_pair, options);
+    let regions = get_mismatch_regions(&mismatched_pixels);
+
+    let comparison_result = match diff_location {
+        Some(diff_location) if is_pair_mismatched(&mismatched_pixels, &animation_comparison) => {
+            create_mismatched_image(
+                image_pair,
+                mismatched_pixels,
+                regions,
+                animation_comparison,
+                diff_location,
+                options,
+            )?
+        }
+        _ => ComparisonResult::new(image_pair, mismatched_pixels, None)
+            .with_regions(regions)
+            .with_animation(animation_comparison.map(|comparison| comparison.result)),
+    }
+    .with_profiles(profiles);
+
+    Logger::info(format!(
+        "Mismatched pixels: '{}' of '{}' ({:.2}%)",
+        comparison_result.get_mismatched_pixels().len(),
+        comparison_result.get_pixel_count(),
+        comparison_result.get_mismatch_percentage()
+    ));
+    if let Some(animation) = comparison_result.get_animation() {
+        Logger::info(format!(
+            "Animation frames - original: '{}', latest: '{}', matching: '{}'",
+            animation.original_frame_count,
+            animation.latest_frame_count,
+            animation.is_matching()
+        ));
+    }
+    if let Some(profiles) = comparison_result.get_profiles() {
+        Logger::info(format!(
+            "Colour profiles - original: '{}', latest: '{}', converted: '{}'",
+            profiles.original.as_deref().unwrap_or("none"),
+            profiles.latest.as_deref().unwrap_or("none"),
+            profiles.converted
+        ));
+    }
+    if let Some(location) = comparison_result.get_comparison_location() {
+        Logger::info(format!("Wrote mismatch image: '{location}'"));
+    }
+    if let Some(location) = comparison_result.get_blink_location() {
+        Logger::info(format!("Wrote blink gif: '{location}'"));
+    }
+    if !comparison_result.get_crops().is_empty() {
+        Logger::info(format!(
+            "Wrote region crops: '{}'",
+            comparison_result.get_crops().len()
+        ));
+    }
+
+    Ok(comparison_result)
+}
+
+/// Creates the mismatch image for the pair, as set by the options' diff options, and writes it to
+/// the location with its extension replaced by the diff format's.
+///
+/// For animated pairs the mismatch image is an animated gif, with a diff of each frame, written in
+/// place of the location with its extension replaced by gif. When set by the diff options, a blink
+/// gif of the pair is also written alongside the location, such as `file.blink.gif`, as are zoomed
+/// crops of the regions of mismatched pixels of still pairs.
+///
+/// The regions are those of the mismatched pixels, as found by `get_mismatch_regions`.
+pub fn create_mismatched_image(
+    image_pair: (ImageHolder, ImageHolder),
+    mismatched_pixels: Vec<PixelCoord>,
+    regions: Vec<MismatchRegion>,
+    animation_comparison: Option<AnimationComparison>,
+    mismatched_location: &str,
+    options: &CompareOptions,
+) -> Result<ComparisonResult, IVCError> {
+    let comparison_image = match &animation_comparison {
+        Some(animation_comparison) => save_animation_to_file_location(
+            create_mismatch_frames(
+                &image_pair,
+                &animation_comparison.frame_mismatched_pixels,
+                options,
+            ),
+            &get_animated_mismatched_image_location(mismatched_location),
+        )?,
+        None => save_image_to_file_location(
+            create_mismatch_image(&image_pair, &mismatched_pixels, &regions, options),
+            &get_formatted_mismatched_image_location(
+                mismatched_location,
+                options.get_diff().get_format(),
+            ),
+        )?,
+    };
+
+    let crops = match options.get_diff().has_crops() && animation_comparison.is_none() {
+        true => save_region_crops(
+            &image_pair,
+            &comparison_image.image,
+            &mismatched_pixels,
+            &regions,
+            mismatched_location,
+            options,
+        )?,
+        false => Vec::new(),
+    };
+
+    let blink_location = match options.get_diff().has_blink() {
+        true => {
+            let blink_location = get_blink_location(mismatched_location);
+            save_animation_to_file_location(
+                create_blink_frames(&image_pair, &regions, options),
+                &blink_location,
+            )?;
+            Some(blink_location)
+        }
+        false => None,
+    };
+
+    Ok(
+        ComparisonResult::new(image_pair, mismatched_pixels, Some(comparison_image))
+            .with_regions(regions)
+            .with_animation(animation_comparison.map(|comparison| comparison.result))
+            .with_blink_location(blink_location)
+            .with_crops(crops),
+    )
+}
+
+/// Writes zoomed crops of the original, latest and diff image for the largest regions of
+/// mismatched pixels alongside the mismatch location, such as `file.region1.original.png`.
+///
+/// The diff crops are taken from the diff image when it lines up with the pair, otherwise, such
+/// as for the triptych layout, from a diff only image in the same style.
+fn save_region_crops(
+    image_pair: &(ImageHolder, ImageHolder),
+    diff_image: &DynamicImage,
+    mismatched_pixels: &[PixelCoord],
+    regions: &[MismatchRegion],
+    mismatched_location: &str,
+    options: &CompareOptions,
+) -> Result<Vec<RegionCrop>, IVCError> {
+    let diff_image = match diff_image.dimensions() == image_pair.1.image.dimensions() {
+        true => diff_image.to_owned(),
+        false => create_mismatch_image(
+            image_pair,
+            mismatched_pixels,
+            regions,
+            &options
+                .to_owned()
+                .with_diff(options.get_diff().with_layout(DiffLayout::DiffOnly)),
+        ),
+    };
+    let format = options.get_diff().get_format();
+    let mut crops = Vec::new();
+
+    for (index, region) in get_crop_regions(regions).into_iter().enumerate() {
+        let save_crop = |image: &DynamicImage, image_name: &str| {
+            let location =
+                get_region_crop_location(mismatched_location, index + 1, image_name, format);
+            save_image_to_file_location(create_crop_thumbnail(image, &region), &location)
+                .map(|_| location)
+        };
+
+        crops.push(RegionCrop {
+            region,
+            original: save_crop(&image_pair.0.image, "original")?,
+            latest: save_crop(&image_pair.1.image, "latest")?,
+            diff: save_crop(&diff_image, "diff")?,
+        });
+    }
+
+    Ok(crops)
+}
+
+/// Compares the pair, frame by frame when either image is animated, in which case the mismatched
+/// pixels are those mismatched in any frame.
+pub fn compare_pair_of_images_and_frames(
+    images: &(ImageHolder, ImageHolder),
+    options: &CompareOptions,
+) -> (Vec<PixelCoord>, Option<AnimationComparison>) {
+    match compare_animation_frames(images, options) {
+        Some(animation_comparison) => (
+            animation_comparison.get_mismatched_pixels(),
+            Some(animation_comparison),
+        ),
+        None => (compare_pair_of_images(images, options), None),
+    }
+}
+
+/// Whether any pixels are mismatched or, for animated pairs, their frame counts or delays differ.
+pub fn is_pair_mismatched(
+    mismatched_pixels: &[PixelCoord],
+    animation_comparison: &Option<AnimationComparison>,
+) -> bool {
+    !mismatched_pixels.is_empty()
+        || animation_comparison
+            .as_ref()
+            .is_some_and(|comparison| !comparison.result.is_matching())
+}
+
+/// Compares two in-memory images, returning whether they match, the pixels that differ past
+/// the tolerance in the options, or that their dimensions differ.
+///
+/// ```
+/// use image::DynamicImage;
+/// use image_visual_comparator::{compare_images, CompareOptions, ComparisonOutcome};
+///
+/// let original = DynamicImage::new_rgba8(4, 4);
+/// let latest = DynamicImage::new_rgba8(4, 4);
+///
+/// let outcome = compare_images(&original, &latest, &CompareOptions::default());
+///
+/// assert_eq!(ComparisonOutcome::Matched, outcome);
+/// ```
+pub fn compare_images(
+    original: &DynamicImage,
+    latest: &DynamicImage,
+    options: &CompareOptions,
+) -> ComparisonOutcome {
+    if original.dimensions() != latest.dimensions() {
+        return ComparisonOutcome::DimensionMismatch {
+            original: original.dimensions(),
+            latest: latest.dimensions(),
+        };
+    }
+
+    let mismatched_pixels = get_mismatched_pixels(original, latest, options);
+
+    if mismatched_pixels.is_empty() {
+        return ComparisonOutcome::Matched;
+    }
+
+    ComparisonOutcome::Mismatched { mismatched_pixels }
+}
+
 #[doc(hidden)]
 /// Compares two images and returns a vector of pixel co-ordinates of pixels who differ past the desired tolerance.
 ///
 /// The location strings passed in should be relative to the directory the program is being run in.
 ///
-/// The passed in tolerance will be used as a reference of whether two pixels with the same co-ordinates
-/// differ between two images. The comparison is performed by converting the pixels to lab colours and checking
-/// their squared distance, it is that distance which is checked against the tolerance.
+/// The tolerance in the options will be used as a reference of whether two pixels with the same co-ordinates
+/// differ between two images. By default the comparison is performed by converting the pixels to lab colours and
+/// checking their squared distance, it is that distance which is checked against the tolerance.
 ///
-/// For instance if an exact match is desired then a value of 0_f32 should be passed in. To allow for more relaxed
-/// standards simply use a higher number.
+/// For instance if an exact match is desired then a tolerance of 0_f32 should be used. To allow for more relaxed
+/// standards simply use a higher number. Pixels within the masks of the options are never mismatched, and no
+/// pixels are returned when the mismatched percentage is within the options' threshold.
 pub fn compare_pair_of_images(
     images: &(ImageHolder, ImageHolder),
-    pixel_tolerance: f32,
+    options: &CompareOptions,
 ) -> Vec<PixelCoord> {
-    let (width, height) = images.0.image.dimensions();
+    get_mismatched_pixels(&images.0.image, &images.1.image, options)
+}
 
+/// Compares each frame found in both images when either image is animated, a still image being
+/// treated as a single frame with no delay. Returns `None` when neither image is animated.
+fn compare_animation_frames(
+    images: &(ImageHolder, ImageHolder),
+    options: &CompareOptions,
+) -> Option<AnimationComparison> {
+    if !images.0.is_animated() && !images.1.is_animated() {
+        return None;
+    }
+
+    let original_frames = images.0.get_frames();
+    let latest_frames = images.1.get_frames();
+
+    let frame_mismatched_pixels: Vec<Vec<PixelCoord>> = original_frames
+        .iter()
+        .zip(latest_frames.iter())
+        .map(|(original_frame, latest_frame)| {
+            get_mismatched_pixels(&original_frame.image, &latest_frame.image, options)
+        })
+        .collect();
+
+    let frames = original_frames
+        .iter()
+        .zip(latest_frames.iter())
+        .zip(frame_mismatched_pixels.iter())
+        .enumerate()
+        .map(
+            |(index, ((original_frame, latest_frame), mismatched_pixels))| FrameResult {
+                index,
+                original_delay_ms: original_frame.delay_ms,
+                latest_delay_ms: latest_frame.delay_ms,
+                mismatched_pixel_count: mismatched_pixels.len(),
+            },
+        )
+        .collect();
+
+    Some(AnimationComparison {
+        result: AnimationResult {
+            original_frame_count: original_frames.len(),
+            latest_frame_count: latest_frames.len(),
+            frames,
+        },
+        frame_mismatched_pixels,
+    })
+}
+
+fn get_mismatched_pixels(
+    image_one: &DynamicImage,
+    image_two: &DynamicImage,
+    options: &CompareOptions,
+) -> Vec<PixelCoord> {
+    if is_comparing_at_native_precision(image_one, image_two, options) {
+        let (image_one, image_two) = (image_one.to_rgba32f(), image_two.to_rgba32f());
+        return collect_mismatched_pixels(image_one.dimensions(), options, |pixel_coord| {
+            is_native_pixel_for_images_matching(&image_one, &image_two, pixel_coord, options)
+        });
+    }
+
+    collect_mismatched_pixels(image_one.dimensions(), options, |pixel_coord| {
+        is_pixel_for_images_matching(image_one, image_two, pixel_coord, options)
+    })
+}
+
+fn collect_mismatched_pixels(
+    (width, height): (u32, u32),
+    options: &CompareOptions,
+    is_matching: impl Fn(&PixelCoord) -> bool,
+) -> Vec<PixelCoord> {
     let mut mismatched_pixels: Vec<PixelCoord> = Vec::new();
 
     for y in 0..height {
         for x in 0..width {
             let pixel_coord = PixelCoord::new(x, y);
-            let is_matching = is_pixel_for_images_matching(images, &pixel_coord, pixel_tolerance);
-            if !is_matching {
+            if options.is_masked(&pixel_coord) {
+                continue;
+            }
+
+            if !is_matching(&pixel_coord) {
                 mismatched_pixels.push(pixel_coord);
             }
         }
     }
 
+    if options.is_within_threshold(mismatched_pixels.len(), width as u64 * height as u64) {
+        return Vec::new();
+    }
+
     mismatched_pixels
 }
 
@@ -112,7 +476,7 @@ mod tests {
                         test_helpers::create_image_holders,
                     },
                 },
-                models::PixelCoord,
+                models::{CompareOptions, PixelCoord},
                 test_utils::{
                     files::{create_temp_dir_handler, get_image_locations},
                     image::create_dynamic_image,
@@ -138,7 +502,7 @@ mod tests {
                     &image_two_location,
                 );
 
-                let result = compare_pair_of_images(&images, 5_f32);
+                let result = compare_pair_of_images(&images, &CompareOptions::new(5_f32));
                 let expected: Vec<PixelCoord> = vec![];
 
                 assert_eq!(
@@ -192,7 +556,7 @@ mod tests {
                 let _ = images.0.image.save(&images.0.location);
                 let _ = images.1.image.save(&images.1.location);
 
-                let result = compare_pair_of_images(&images, 5_f32);
+                let result = compare_pair_of_images(&images, &CompareOptions::new(5_f32));
                 let expected: Vec<PixelCoord> = vec![];
 
                 assert_ne!(
@@ -206,12 +570,15 @@ mod tests {
         mod with_pixel_coords {
             use image::GenericImageView;
 
-            use crate::compare::{
-                compare_pair_of_images,
-                tests::returns_vector::{
-                    setup_and_return_required_data, update_image_for_pixels,
-                    PIXEL_COLOUR_OUTSIDE_TOLERANCE, PIXEL_COLOUR_WITHIN_TOLERANCE,
+            use crate::{
+                compare::{
+                    compare_pair_of_images,
+                    tests::returns_vector::{
+                        setup_and_return_required_data, update_image_for_pixels,
+                        PIXEL_COLOUR_OUTSIDE_TOLERANCE, PIXEL_COLOUR_WITHIN_TOLERANCE,
+                    },
                 },
+                models::CompareOptions,
             };
 
             #[test]
@@ -263,7 +630,7 @@ mod tests {
                 let _ = images.0.image.save(&images.0.location);
                 let _ = images.1.image.save(&images.1.location);
 
-                let result = compare_pair_of_images(&images, 5_f32);
+                let result = compare_pair_of_images(&images, &CompareOptions::new(5_f32));
                 let expected = vec![pixel_coord_one, pixel_coord_two, pixel_coord_three];
 
                 assert_ne!(
@@ -275,6 +642,499 @@ mod tests {
         }
     }
 
+    mod compare_images {
+        use image::{DynamicImage, ImageBuffer, Rgba};
+
+        use crate::{
+            compare::compare_images,
+            models::{
+                ColourMetric, ColourPrecision, CompareOptions, ComparisonOutcome, Mask, PixelCoord,
+            },
+            test_utils::image::{change_pixel_on_img, create_dynamic_image},
+        };
+
+        #[test]
+        fn returns_matched_when_images_are_an_exact_match() {
+            let original = create_dynamic_image(5, 5);
+            let latest = create_dynamic_image(5, 5);
+
+            let result = compare_images(&original, &latest, &CompareOptions::default());
+
+            assert_eq!(ComparisonOutcome::Matched, result);
+            assert!(result.is_match());
+        }
+
+        #[test]
+        fn returns_mismatched_pixels_when_images_differ_past_the_tolerance() {
+            let original = create_dynamic_image(5, 5);
+            let mut latest = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut latest, 3, 3);
+
+            let result = compare_images(&original, &latest, &CompareOptions::new(0_f32));
+
+            assert_eq!(
+                ComparisonOutcome::Mismatched {
+                    mismatched_pixels: vec![PixelCoord::new(3, 3)]
+                },
+                result
+            );
+        }
+
+        #[test]
+        fn returns_matched_when_mismatched_pixels_are_masked_or_within_threshold() {
+            let original = create_dynamic_image(5, 5);
+            let mut latest = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut latest, 3, 3);
+
+            let masked_options = CompareOptions::new(0_f32).with_masks([Mask::new(2, 2, 2, 2)]);
+            let threshold_options = CompareOptions::new(0_f32).with_threshold(4_f32);
+
+            assert!(compare_images(&original, &latest, &masked_options).is_match());
+            assert!(compare_images(&original, &latest, &threshold_options).is_match());
+            assert!(!compare_images(
+                &original,
+                &latest,
+                &CompareOptions::new(0_f32).with_threshold(3.9)
+            )
+            .is_match());
+        }
+
+        #[test]
+        fn only_sees_differences_smaller_than_an_eight_bit_step_at_native_precision() {
+            let original = DynamicImage::ImageRgba16(ImageBuffer::from_pixel(
+                2,
+                2,
+                Rgba([1000_u16, 2000, 3000, 65535]),
+            ));
+            let mut latest = original.to_rgba16();
+            latest.put_pixel(1, 1, Rgba([1001_u16, 2000, 3000, 65535]));
+            let latest = DynamicImage::ImageRgba16(latest);
+
+            for metric in [
+                ColourMetric::LabSquared,
+                ColourMetric::Lab,
+                ColourMetric::Rgba,
+            ] {
+                let options = CompareOptions::new(0_f32).with_metric(metric);
+
+                assert!(compare_images(&original, &latest, &options).is_match());
+                assert_eq!(
+                    ComparisonOutcome::Mismatched {
+                        mismatched_pixels: vec![PixelCoord::new(1, 1)]
+                    },
+                    compare_images(
+                        &original,
+                        &latest,
+                        &options.with_precision(ColourPrecision::Native)
+                    ),
+                    "metric: '{metric:?}'"
+                );
+            }
+        }
+
+        #[test]
+        fn compares_float_channels_above_one_with_rgba_metric_at_native_precision() {
+            let original = DynamicImage::ImageRgba32F(ImageBuffer::from_pixel(
+                2,
+                2,
+                Rgba([2_f32, 0.5, 0.5, 1_f32]),
+            ));
+            let latest = DynamicImage::ImageRgba32F(ImageBuffer::from_pixel(
+                2,
+                2,
+                Rgba([3_f32, 0.5, 0.5, 1_f32]),
+            ));
+            let options = CompareOptions::new(100_f32).with_precision(ColourPrecision::Native);
+
+            assert!(compare_images(&original, &latest, &options).is_match());
+            assert!(
+                !compare_images(&original, &latest, &options.with_metric(ColourMetric::Rgba))
+                    .is_match()
+            );
+        }
+
+        #[test]
+        fn returns_dimension_mismatch_when_dimensions_differ() {
+            let original = create_dynamic_image(5, 5);
+            let latest = create_dynamic_image(5, 4);
+
+            let result = compare_images(&original, &latest, &CompareOptions::default());
+
+            assert_eq!(
+                ComparisonOutcome::DimensionMismatch {
+                    original: (5, 5),
+                    latest: (5, 4)
+                },
+                result
+            );
+        }
+    }
+
+    mod compare_image_files {
+        use std::path::Path;
+
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use crate::{
+            compare::compare_image_files,
+            models::{
+                AnimationFrame, CompareOptions, DiffFormat, DiffLayout, DiffOptions, FrameResult,
+                ProfileHandling, ProfileResult, SvgOptions,
+            },
+            test_utils::{
+                files::{create_temp_dir_handler, get_image_locations},
+                image::{
+                    change_pixel_on_img, create_dynamic_image, save_png_with_icc_profile,
+                    SWAPPED_ICC_PROFILE,
+                },
+            },
+            utils::file_system::images::{
+                get_pair_of_images_from_file_locations, save_animation_to_file_location,
+            },
+        };
+
+        #[test]
+        fn returns_result_and_writes_diff_when_images_are_mismatched() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let diff_location = temp_dir_holder.get_location_of_file_name("out/diff.png");
+            let mut latest = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut latest, 3, 3);
+            create_dynamic_image(5, 5).save(&original_location).unwrap();
+            latest.save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default(),
+            )
+            .unwrap();
+
+            assert_eq!(1, result.get_mismatched_pixels().len());
+            assert_eq!(25, result.get_pixel_count());
+            assert_eq!(4_f32, result.get_mismatch_percentage());
+            assert_eq!(
+                Some(diff_location.as_str()),
+                result.get_comparison_location()
+            );
+            assert!(Path::new(&diff_location).is_file());
+        }
+
+        #[test]
+        fn writes_diff_in_the_diff_format_with_its_extension_replaced() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let diff_location = temp_dir_holder.get_location_of_file_name("diff.png");
+            let mut latest = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut latest, 3, 3);
+            create_dynamic_image(5, 5).save(&original_location).unwrap();
+            latest.save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default().with_diff(
+                    DiffOptions::default()
+                        .with_format(DiffFormat::Webp)
+                        .with_layout(DiffLayout::Triptych),
+                ),
+            )
+            .unwrap();
+
+            let webp_location = temp_dir_holder.get_location_of_file_name("diff.webp");
+            assert_eq!(
+                Some(webp_location.as_str()),
+                result.get_comparison_location()
+            );
+            assert!(!Path::new(&diff_location).exists());
+            assert_eq!((15, 5), image::image_dimensions(&webp_location).unwrap());
+        }
+
+        #[test]
+        fn writes_blink_gif_alongside_diff_when_set() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let diff_location = temp_dir_holder.get_location_of_file_name("diff.png");
+            let mut latest = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut latest, 3, 3);
+            create_dynamic_image(5, 5).save(&original_location).unwrap();
+            latest.save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default().with_diff(DiffOptions::default().with_blink(true)),
+            )
+            .unwrap();
+
+            let blink_location = temp_dir_holder.get_location_of_file_name("diff.blink.gif");
+            assert_eq!(Some(blink_location.as_str()), result.get_blink_location());
+            let blink_image = get_pair_of_images_from_file_locations(
+                &blink_location,
+                &blink_location,
+                &SvgOptions::default(),
+            )
+            .unwrap()
+            .0;
+            assert_eq!(2, blink_image.get_frames().len());
+        }
+
+        #[test]
+        fn writes_region_crops_alongside_diff_when_set() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let diff_location = temp_dir_holder.get_location_of_file_name("diff.png");
+            let mut latest = create_dynamic_image(40, 40);
+            for (x, y) in [(3, 3), (30, 30), (31, 30)] {
+                latest
+                    .as_mut_rgba8()
+                    .unwrap()
+                    .put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            }
+            create_dynamic_image(40, 40)
+                .save(&original_location)
+                .unwrap();
+            latest.save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default().with_diff(
+                    DiffOptions::default()
+                        .with_layout(DiffLayout::Triptych)
+                        .with_crops(true),
+                ),
+            )
+            .unwrap();
+
+            let crops = result.get_crops();
+            assert_eq!(2, crops.len());
+            assert_eq!((30, 30, 2, 1), {
+                let region = crops[0].region;
+                (region.x, region.y, region.width, region.height)
+            });
+            assert_eq!(
+                temp_dir_holder.get_location_of_file_name("diff.region1.diff.png"),
+                crops[0].diff
+            );
+            for location in [&crops[1].original, &crops[1].latest, &crops[1].diff] {
+                assert_eq!((192, 192), image::image_dimensions(location).unwrap());
+            }
+        }
+
+        #[test]
+        fn returns_result_without_writing_diff_when_images_match() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let diff_location = temp_dir_holder.get_location_of_file_name("diff.png");
+            create_dynamic_image(5, 5).save(&original_location).unwrap();
+            create_dynamic_image(5, 5).save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default(),
+            )
+            .unwrap();
+
+            assert!(result.get_mismatched_pixels().is_empty());
+            assert_eq!(None, result.get_comparison_location());
+            assert!(!Path::new(&diff_location).exists());
+        }
+
+        #[test]
+        fn compares_animations_frame_by_frame_and_writes_animated_diff() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let original_location = temp_dir_holder.get_location_of_file_name("original.gif");
+            let latest_location = temp_dir_holder.get_location_of_file_name("latest.gif");
+            let diff_location = temp_dir_holder.get_location_of_file_name("diff.png");
+            let mut changed_frame = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut changed_frame, 3, 3);
+            let frame = |image, delay_ms| AnimationFrame::new(image, delay_ms);
+            save_animation_to_file_location(
+                vec![
+                    frame(create_dynamic_image(5, 5), 100),
+                    frame(create_dynamic_image(5, 5), 100),
+                ],
+                &original_location,
+            )
+            .unwrap();
+            save_animation_to_file_location(
+                vec![
+                    frame(create_dynamic_image(5, 5), 200),
+                    frame(changed_frame, 100),
+                    frame(create_dynamic_image(5, 5), 100),
+                ],
+                &latest_location,
+            )
+            .unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                Some(&diff_location),
+                &CompareOptions::default(),
+            )
+            .unwrap();
+
+            let animation = result.get_animation().unwrap();
+            assert_eq!(2, animation.original_frame_count);
+            assert_eq!(3, animation.latest_frame_count);
+            assert_eq!(
+                vec![
+                    FrameResult {
+                        index: 0,
+                        original_delay_ms: 100,
+                        latest_delay_ms: 200,
+                        mismatched_pixel_count: 0,
+                    },
+                    FrameResult {
+                        index: 1,
+                        original_delay_ms: 100,
+                        latest_delay_ms: 100,
+                        mismatched_pixel_count: 1,
+                    },
+                ],
+                animation.frames
+            );
+            assert!(result.is_mismatched());
+
+            let animated_diff_location = temp_dir_holder.get_location_of_file_name("diff.gif");
+            assert_eq!(
+                Some(animated_diff_location.as_str()),
+                result.get_comparison_location()
+            );
+            let (diff, _) = get_pair_of_images_from_file_locations(
+                &animated_diff_location,
+                &latest_location,
+                &SvgOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(3, diff.frames.len());
+        }
+
+        #[test]
+        fn is_mismatched_when_only_animation_delays_differ() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            for (location, delay_ms) in [(&original_location, 100), (&latest_location, 150)] {
+                save_animation_to_file_location(
+                    vec![
+                        AnimationFrame::new(create_dynamic_image(5, 5), 100),
+                        AnimationFrame::new(create_dynamic_image(5, 5), delay_ms),
+                    ],
+                    location,
+                )
+                .unwrap();
+            }
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                None,
+                &CompareOptions::default(),
+            )
+            .unwrap();
+
+            assert!(result.get_mismatched_pixels().is_empty());
+            assert!(result.is_mismatched());
+        }
+
+        #[test]
+        fn rasterises_svg_images_at_the_svg_scale() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let original_location = temp_dir_holder.get_location_of_file_name("original.svg");
+            let latest_location = temp_dir_holder.get_location_of_file_name("latest.svg");
+            let svg = |extra_shape: &str| {
+                format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"4\" height=\"4\"><rect width=\"4\" height=\"4\" fill=\"#f00\"/>{extra_shape}</svg>")
+            };
+            std::fs::write(&original_location, svg("")).unwrap();
+            std::fs::write(
+                &latest_location,
+                svg("<rect width=\"1\" height=\"1\" fill=\"#00f\"/>"),
+            )
+            .unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                None,
+                &CompareOptions::default().with_svg(SvgOptions::default().with_scale(2_f32)),
+            )
+            .unwrap();
+
+            assert_eq!(64, result.get_pixel_count());
+            assert_eq!(4, result.get_mismatched_pixels().len());
+        }
+
+        #[test]
+        fn converts_embedded_colour_profiles_to_srgb_when_set() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            let image =
+                |pixel: [u8; 4]| DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 3, Rgba(pixel)));
+            image([0, 0, 255, 255]).save(&original_location).unwrap();
+            save_png_with_icc_profile(
+                &image([255, 0, 0, 255]),
+                SWAPPED_ICC_PROFILE,
+                &latest_location,
+            );
+
+            let warned_result = compare_image_files(
+                &original_location,
+                &latest_location,
+                None,
+                &CompareOptions::default(),
+            )
+            .unwrap();
+            let converted_result = compare_image_files(
+                &original_location,
+                &latest_location,
+                None,
+                &CompareOptions::default().with_profiles(ProfileHandling::Convert),
+            )
+            .unwrap();
+
+            assert_eq!(9, warned_result.get_mismatched_pixels().len());
+            assert!(converted_result.get_mismatched_pixels().is_empty());
+            assert_eq!(
+                Some(&ProfileResult {
+                    original: None,
+                    latest: Some("Swapped".to_string()),
+                    converted: true,
+                }),
+                converted_result.get_profiles()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_dimensions_do_not_match() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let (original_location, latest_location) = get_image_locations(&temp_dir_holder);
+            create_dynamic_image(5, 5).save(&original_location).unwrap();
+            create_dynamic_image(4, 5).save(&latest_location).unwrap();
+
+            let result = compare_image_files(
+                &original_location,
+                &latest_location,
+                None,
+                &CompareOptions::default(),
+            );
+
+            assert_eq!(
+                format!(
+                    "Image dimensions do not match: '{original_location}' and '{latest_location}'."
+                ),
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+
     mod test_helpers {
         use image::DynamicImage;
 
@@ -0,0 +1,85 @@
+use clap::{Args, Subcommand};
+
+const DEFAULT_SERVE_PORT: u16 = 8080;
+
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Compare a single original and latest image file
+    Compare(ComparePairOptions),
+    /// Promote latest images from the last run to be the new original images
+    Approve(ApproveOptions),
+    /// Restore original images from a snapshot archived by an earlier approval
+    Rollback(RollbackOptions),
+    /// Serve a local page for reviewing the last run, approving or rejecting each mismatched pair
+    Serve(ServeOptions),
+    /// Combine the results of every shard of a run into one results file and report
+    MergeReports(MergeReportsOptions),
+}
+
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct ApproveOptions {
+    #[arg(
+        long,
+        value_name = "GLOB",
+        help = "only approve images matching the glob (repeatable)",
+        long_help = "Only approve images whose path, relative to the latest or original directory, matches the glob, e.g. 'checkout/**'.\n\nCan be passed multiple times. Approves every mismatched and new image when not passed."
+    )]
+    pub only: Vec<String>,
+
+    #[arg(
+        long,
+        help = "remove original images that have no latest image",
+        long_help = "Remove original images that had no matching latest image in the last run, as the image has been deleted."
+    )]
+    pub remove_deleted: bool,
+}
+
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct RollbackOptions {
+    #[arg(
+        help = "id of the snapshot to restore [default: most recent]",
+        long_help = "The id of the snapshot to restore, as shown by --list. Defaults to the most recent snapshot."
+    )]
+    pub snapshot: Option<String>,
+
+    #[arg(long, help = "list snapshots instead of restoring one")]
+    pub list: bool,
+}
+
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct ComparePairOptions {
+    #[arg(help = "location of the original image")]
+    pub original: String,
+
+    #[arg(help = "location of the latest image")]
+    pub latest: String,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "location to write the mismatch image to",
+        long_help = "The location to write the mismatch image to, only written when the images are mismatched.\n\nIts extension is replaced by that of --diff-format, or by 'gif' when either image is animated."
+    )]
+    pub out: Option<String>,
+}
+
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ServeOptions {
+    #[arg(
+        long,
+        default_value_t = DEFAULT_SERVE_PORT,
+        help = "port to serve the review page on",
+        long_help = "The port to serve the review page on. Only listens on localhost."
+    )]
+    pub port: u16,
+}
+
+#[derive(Args, Debug, Clone, Default, PartialEq)]
+pub struct MergeReportsOptions {
+    #[arg(
+        value_name = "RESULTS_FILE",
+        help = "shard results files to merge [default: every shard's in the output directory]",
+        long_help = "The results files of the shards to merge. Defaults to every 'results.shard-*.json' file in the output directory."
+    )]
+    pub results: Vec<String>,
+}
@@ -0,0 +1,631 @@
+use std::path::Path;
+
+use log::LevelFilter;
+
+use crate::{
+    models::{
+        ColourMetric, ColourPrecision, CompareOptions, DiffFormat, DiffLayout, DiffOptions,
+        HighlightColour, Mask, ProfileHandling, Shard, SvgOptions,
+    },
+    utils::{
+        file_paths::get_relative_key, file_system::files::is_finding_several_extensions,
+        globs::is_matching_any_glob,
+    },
+};
+
+use super::{PathOverride, DEFAULT_IMAGE_FORMAT, DEFAULT_TOLERANCE};
+
+/// Configuration for a comparison run, created via `CompareConfig::builder()` or converted from
+/// the cli's `AppConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareConfig {
+    directory: String,
+    tolerance: u8,
+    log_level: LevelFilter,
+    original_dir: Option<String>,
+    latest_dir: Option<String>,
+    output_dir: Option<String>,
+    image_extensions: Vec<String>,
+    match_across_formats: bool,
+    metric: ColourMetric,
+    precision: ColourPrecision,
+    profiles: ProfileHandling,
+    threshold: f32,
+    masks: Vec<Mask>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    path_overrides: Vec<PathOverride>,
+    shard: Option<Shard>,
+    svg: SvgOptions,
+    save_svg_rasters: bool,
+    diff: DiffOptions,
+    markdown_file: Option<String>,
+}
+
+const ORIGINAL_IMAGES: &str = "original";
+const LATEST_IMAGES: &str = "latest";
+const MISMATCHED_IMAGES: &str = "mismatched";
+const RESULTS_FILE: &str = "results";
+const REPORT_FILE: &str = "report";
+const HISTORY_DIR: &str = ".ivc/history";
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        CompareConfig {
+            directory: "images".to_string(),
+            tolerance: DEFAULT_TOLERANCE,
+            log_level: LevelFilter::Info,
+            original_dir: None,
+            latest_dir: None,
+            output_dir: None,
+            image_extensions: vec![DEFAULT_IMAGE_FORMAT.to_string()],
+            match_across_formats: false,
+            metric: ColourMetric::default(),
+            precision: ColourPrecision::default(),
+            profiles: ProfileHandling::default(),
+            threshold: 0_f32,
+            masks: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            path_overrides: Vec::new(),
+            shard: None,
+            svg: SvgOptions::default(),
+            save_svg_rasters: false,
+            diff: DiffOptions::default(),
+            markdown_file: None,
+        }
+    }
+}
+
+impl CompareConfig {
+    pub fn builder() -> CompareConfigBuilder {
+        CompareConfigBuilder::default()
+    }
+
+    pub fn get_tolerance(&self) -> f32 {
+        self.tolerance as f32
+    }
+
+    /// The global options for comparing, without any path overrides applied.
+    pub fn get_compare_options(&self) -> CompareOptions {
+        CompareOptions::new(self.get_tolerance())
+            .with_metric(self.metric)
+            .with_precision(self.precision)
+            .with_profiles(self.profiles)
+            .with_threshold(self.threshold)
+            .with_masks(self.masks.to_owned())
+            .with_svg(self.svg)
+            .with_diff(self.diff)
+    }
+
+    /// The options for comparing the latest image at the location, with every path override
+    /// matching its path relative to the latest directory applied in order.
+    pub fn get_compare_options_for(&self, latest_location: &str) -> CompareOptions {
+        let relative_key = get_relative_key(latest_location, &self.get_latest_images_dir())
+            .unwrap_or(latest_location.to_owned());
+
+        let (mut tolerance, mut metric, mut precision, mut threshold, mut masks) = (
+            self.tolerance,
+            self.metric,
+            self.precision,
+            self.threshold,
+            self.masks.to_owned(),
+        );
+
+        for path_override in self
+            .path_overrides
+            .iter()
+            .filter(|path_override| is_matching_any_glob(&path_override.paths, &relative_key))
+        {
+            tolerance = path_override
+                .tolerance
+                .map_or(tolerance, |value| value.min(100));
+            metric = path_override.metric.unwrap_or(metric);
+            precision = path_override.precision.unwrap_or(precision);
+            threshold = path_override.threshold.unwrap_or(threshold);
+            if let Some(override_masks) = &path_override.masks {
+                masks = override_masks.to_owned();
+            }
+        }
+
+        CompareOptions::new(tolerance as f32)
+            .with_metric(metric)
+            .with_precision(precision)
+            .with_profiles(self.profiles)
+            .with_threshold(threshold)
+            .with_masks(masks)
+            .with_svg(self.svg)
+            .with_diff(self.diff)
+    }
+
+    /// Whether the image at the location, found within the root directory, should be left out of
+    /// the comparison. That is when it does not match an include glob, when there are any, or
+    /// matches an exclude glob or an ignoring path override.
+    pub fn is_excluded(&self, location: &str, root_dir: &str) -> bool {
+        let relative_key = get_relative_key(location, root_dir).unwrap_or(location.to_owned());
+
+        (!self.include.is_empty() && !is_matching_any_glob(&self.include, &relative_key))
+            || is_matching_any_glob(&self.exclude, &relative_key)
+            || self.path_overrides.iter().any(|path_override| {
+                path_override.ignore && is_matching_any_glob(&path_override.paths, &relative_key)
+            })
+    }
+
+    /// How svg images are rasterised before being compared.
+    pub fn get_svg_options(&self) -> SvgOptions {
+        self.svg
+    }
+
+    /// Whether the rasterised svg images are written alongside the mismatch images, such as
+    /// `icon.original.png` and `icon.latest.png`.
+    pub fn is_saving_svg_rasters(&self) -> bool {
+        self.save_svg_rasters
+    }
+
+    pub fn get_log_level(&self) -> LevelFilter {
+        self.log_level
+    }
+
+    pub fn get_image_extensions(&self) -> &[String] {
+        &self.image_extensions
+    }
+
+    pub fn is_matching_across_formats(&self) -> bool {
+        self.match_across_formats
+    }
+
+    /// Whether mismatch images keep the latest image's extension in their names, such as
+    /// `file.bmp.png`, which is when images with more than one extension are found and those
+    /// differing only by extension are compared separately, so would otherwise share a name.
+    pub fn is_keeping_extensions_in_mismatched_names(&self) -> bool {
+        is_finding_several_extensions(&self.image_extensions) && !self.match_across_formats
+    }
+
+    pub fn get_original_images_dir(&self) -> String {
+        self.get_dir_or_default(&self.original_dir, ORIGINAL_IMAGES)
+    }
+
+    pub fn get_latest_images_dir(&self) -> String {
+        self.get_dir_or_default(&self.latest_dir, LATEST_IMAGES)
+    }
+
+    pub fn get_mismatched_images_dir(&self) -> String {
+        self.get_dir_or_default(&self.output_dir, MISMATCHED_IMAGES)
+    }
+
+    /// The results file of the last run, written within the mismatched directory. Each shard of
+    /// a sharded run has its own results file, such as `results.shard-1-of-4.json`.
+    pub fn get_results_file_location(&self) -> String {
+        self.get_output_file_location(RESULTS_FILE, "json")
+    }
+
+    /// The html report of the last run, written within the mismatched directory. Each shard of a
+    /// sharded run has its own report, such as `report.shard-1-of-4.html`.
+    pub fn get_report_file_location(&self) -> String {
+        self.get_output_file_location(REPORT_FILE, "html")
+    }
+
+    /// Where the markdown summary of the run is written, `None` when it is not written.
+    pub fn get_markdown_file_location(&self) -> Option<&str> {
+        self.markdown_file.as_deref()
+    }
+
+    /// The shard of the images being compared, `None` when every image is compared.
+    pub fn get_shard(&self) -> Option<Shard> {
+        self.shard
+    }
+
+    /// The same config for the run as a whole, rather than one of its shards, such as for merging
+    /// the results of every shard.
+    pub fn without_shard(&self) -> CompareConfig {
+        CompareConfig {
+            shard: None,
+            ..self.clone()
+        }
+    }
+
+    /// The directory snapshots of original images are archived to before approvals, within
+    /// `directory`.
+    pub fn get_history_dir(&self) -> String {
+        Path::new(&self.directory)
+            .join(HISTORY_DIR)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn get_output_file_location(&self, file_stem: &str, extension: &str) -> String {
+        let file_name = match self.shard {
+            Some(shard) => format!(
+                "{file_stem}.shard-{}-of-{}.{extension}",
+                shard.get_index(),
+                shard.get_count()
+            ),
+            None => format!("{file_stem}.{extension}"),
+        };
+
+        Path::new(&self.get_mismatched_images_dir())
+            .join(file_name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn get_dir_or_default(&self, dir: &Option<String>, default_name: &str) -> String {
+        match dir {
+            Some(dir) => dir.to_string(),
+            None => Path::new(&self.directory)
+                .join(default_name)
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+}
+
+/// Builds a `CompareConfig`, with any option that is not set keeping the same default as the cli.
+#[derive(Debug, Default)]
+pub struct CompareConfigBuilder {
+    config: CompareConfig,
+}
+
+impl CompareConfigBuilder {
+    /// The directory containing the original, latest and mismatched image directories.
+    pub fn directory(mut self, directory: impl Into<String>) -> Self {
+        self.config.directory = directory.into();
+        self
+    }
+
+    /// The tolerance for pixel difference (0 - 100), values above 100 are treated as 100.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.config.tolerance = tolerance.min(100);
+        self
+    }
+
+    pub fn log_level(mut self, log_level: LevelFilter) -> Self {
+        self.config.log_level = log_level;
+        self
+    }
+
+    /// The directory containing the original images, used as is rather than being joined onto
+    /// `directory`. Defaults to `<directory>/original`.
+    pub fn original_dir(mut self, original_dir: impl Into<String>) -> Self {
+        self.config.original_dir = Some(original_dir.into());
+        self
+    }
+
+    /// The directory containing the latest images, used as is rather than being joined onto
+    /// `directory`. Defaults to `<directory>/latest`.
+    pub fn latest_dir(mut self, latest_dir: impl Into<String>) -> Self {
+        self.config.latest_dir = Some(latest_dir.into());
+        self
+    }
+
+    /// The directory mismatch images are written to, used as is rather than being joined onto
+    /// `directory`. Defaults to `<directory>/mismatched`.
+    pub fn output_dir(mut self, output_dir: impl Into<String>) -> Self {
+        self.config.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// The extensions of the image files to compare, such as `png` or `webp`. Images are decoded
+    /// based on their content, the extension is only used to find the files.
+    pub fn image_extensions<T: Into<String>>(
+        mut self,
+        image_extensions: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.config.image_extensions = image_extensions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether an original and latest image should be paired when only their extensions differ,
+    /// such as `foo.png` and `foo.webp`.
+    pub fn match_across_formats(mut self, match_across_formats: bool) -> Self {
+        self.config.match_across_formats = match_across_formats;
+        self
+    }
+
+    /// How the difference between two pixels is measured, defaults to the squared distance
+    /// between their lab colours.
+    pub fn metric(mut self, metric: ColourMetric) -> Self {
+        self.config.metric = metric;
+        self
+    }
+
+    /// The precision pixels are compared at, defaults to converting every pixel to 8 bits per
+    /// channel.
+    pub fn precision(mut self, precision: ColourPrecision) -> Self {
+        self.config.precision = precision;
+        self
+    }
+
+    /// How the icc colour profiles embedded in images are handled, defaults to warning when the
+    /// profiles of a pair differ.
+    pub fn profiles(mut self, profiles: ProfileHandling) -> Self {
+        self.config.profiles = profiles;
+        self
+    }
+
+    /// The percentage of pixels (0 - 100) that may be mismatched with a pair still being treated
+    /// as matched, values above 100 are treated as 100.
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.config.threshold = threshold.clamp(0_f32, 100_f32);
+        self
+    }
+
+    /// Areas of every image whose pixels are never compared.
+    pub fn masks(mut self, masks: impl Into<Vec<Mask>>) -> Self {
+        self.config.masks = masks.into();
+        self
+    }
+
+    /// Globs of image paths, relative to the original or latest directory, of the only images
+    /// that are compared. Every image is compared when there are none.
+    pub fn include<T: Into<String>>(mut self, include: impl IntoIterator<Item = T>) -> Self {
+        self.config.include = include.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Globs of image paths, relative to the original or latest directory, that are not compared,
+    /// even when they match an include glob.
+    pub fn exclude<T: Into<String>>(mut self, exclude: impl IntoIterator<Item = T>) -> Self {
+        self.config.exclude = exclude.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds options for the images matching the override's globs, applied after any overrides
+    /// already added.
+    pub fn path_override(mut self, path_override: PathOverride) -> Self {
+        self.config.path_overrides.push(path_override);
+        self
+    }
+
+    /// Only compares the images assigned to the shard, by their hashed relative path, writing the
+    /// results to files of the shard's own.
+    pub fn shard(mut self, shard: Shard) -> Self {
+        self.config.shard = Some(shard);
+        self
+    }
+
+    /// How many pixels each svg user unit is rasterised to, defaults to 1.
+    pub fn svg_scale(mut self, svg_scale: f32) -> Self {
+        self.config.svg = self.config.svg.with_scale(svg_scale);
+        self
+    }
+
+    /// The dpi svg lengths in physical units, such as `mm`, are converted at, defaults to 96.
+    pub fn svg_dpi(mut self, svg_dpi: f32) -> Self {
+        self.config.svg = self.config.svg.with_dpi(svg_dpi);
+        self
+    }
+
+    /// Whether the rasterised svg images of each pair are written alongside its mismatch image.
+    pub fn save_svg_rasters(mut self, save_svg_rasters: bool) -> Self {
+        self.config.save_svg_rasters = save_svg_rasters;
+        self
+    }
+
+    /// The format diff images are written in, defaults to png. Animated diffs are always gif.
+    pub fn diff_format(mut self, diff_format: DiffFormat) -> Self {
+        self.config.diff = self.config.diff.with_format(diff_format);
+        self
+    }
+
+    /// How the images of a mismatched pair are laid out in its diff image, defaults to the latest
+    /// image with its mismatched pixels highlighted.
+    pub fn diff_layout(mut self, diff_layout: DiffLayout) -> Self {
+        self.config.diff = self.config.diff.with_layout(diff_layout);
+        self
+    }
+
+    /// The colour mismatched pixels are highlighted in, defaults to red.
+    pub fn diff_highlight(mut self, diff_highlight: HighlightColour) -> Self {
+        self.config.diff = self.config.diff.with_highlight(diff_highlight);
+        self
+    }
+
+    /// The percentage (0 - 100) the unchanged pixels of diff images are faded towards white,
+    /// values above 100 are treated as 100.
+    pub fn diff_fade(mut self, diff_fade: f32) -> Self {
+        self.config.diff = self.config.diff.with_fade(diff_fade.clamp(0_f32, 100_f32));
+        self
+    }
+
+    /// Whether each region of mismatched pixels is outlined in diff images.
+    pub fn diff_outlines(mut self, diff_outlines: bool) -> Self {
+        self.config.diff = self.config.diff.with_outlines(diff_outlines);
+        self
+    }
+
+    /// Whether an animated gif alternating the original and latest image of each mismatched pair
+    /// is written alongside its diff image.
+    pub fn blink(mut self, blink: bool) -> Self {
+        self.config.diff = self.config.diff.with_blink(blink);
+        self
+    }
+
+    /// Whether zoomed crops of the largest regions of mismatched pixels of each still pair are
+    /// written alongside its diff image.
+    pub fn crops(mut self, crops: bool) -> Self {
+        self.config.diff = self.config.diff.with_crops(crops);
+        self
+    }
+
+    /// Where a markdown summary of the run, such as for a pull request comment, is written. Not
+    /// written by default.
+    pub fn markdown_file(mut self, markdown_file: impl Into<String>) -> Self {
+        self.config.markdown_file = Some(markdown_file.into());
+        self
+    }
+
+    pub fn build(self) -> CompareConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod builder {
+        use log::LevelFilter;
+
+        use crate::{config::CompareConfig, models::Shard};
+
+        #[test]
+        fn uses_cli_defaults_when_nothing_is_set() {
+            let config = CompareConfig::builder().build();
+
+            assert_eq!(5_f32, config.get_tolerance());
+            assert_eq!(LevelFilter::Info, config.get_log_level());
+            assert_eq!(["png"], config.get_image_extensions());
+            assert!(!config.is_matching_across_formats());
+            assert_eq!("images/original", config.get_original_images_dir());
+            assert_eq!("images/latest", config.get_latest_images_dir());
+            assert_eq!("images/mismatched", config.get_mismatched_images_dir());
+            assert_eq!(
+                "images/mismatched/results.json",
+                config.get_results_file_location()
+            );
+            assert_eq!(
+                "images/mismatched/report.html",
+                config.get_report_file_location()
+            );
+            assert_eq!("images/.ivc/history", config.get_history_dir());
+        }
+
+        #[test]
+        fn uses_every_option_that_is_set() {
+            let config = CompareConfig::builder()
+                .directory("screenshots")
+                .tolerance(20)
+                .log_level(LevelFilter::Debug)
+                .original_dir("/baseline")
+                .latest_dir("current")
+                .output_dir("../diffs")
+                .image_extensions(["jpg", "webp"])
+                .match_across_formats(true)
+                .build();
+
+            assert_eq!(20_f32, config.get_tolerance());
+            assert_eq!(LevelFilter::Debug, config.get_log_level());
+            assert_eq!(["jpg", "webp"], config.get_image_extensions());
+            assert!(config.is_matching_across_formats());
+            assert_eq!("/baseline", config.get_original_images_dir());
+            assert_eq!("current", config.get_latest_images_dir());
+            assert_eq!("../diffs", config.get_mismatched_images_dir());
+        }
+
+        #[test]
+        fn keeps_extensions_in_mismatched_names_when_images_could_differ_only_by_extension() {
+            let is_keeping = |extensions: &[&str], match_across_formats: bool| {
+                CompareConfig::builder()
+                    .image_extensions(extensions.iter().copied())
+                    .match_across_formats(match_across_formats)
+                    .build()
+                    .is_keeping_extensions_in_mismatched_names()
+            };
+
+            assert!(!is_keeping(&["png"], false));
+            assert!(is_keeping(&["png", "bmp"], false));
+            assert!(is_keeping(&["jpg"], false));
+            assert!(!is_keeping(&["png", "bmp"], true));
+        }
+
+        #[test]
+        fn uses_shard_specific_results_and_report_files_when_sharded() {
+            let config = CompareConfig::builder()
+                .shard(Shard::new(2, 4).unwrap())
+                .build();
+
+            assert_eq!(
+                "images/mismatched/results.shard-2-of-4.json",
+                config.get_results_file_location()
+            );
+            assert_eq!(
+                "images/mismatched/report.shard-2-of-4.html",
+                config.get_report_file_location()
+            );
+            assert_eq!(
+                "images/mismatched/results.json",
+                config.without_shard().get_results_file_location()
+            );
+        }
+
+        #[test]
+        fn caps_tolerance_at_one_hundred() {
+            let config = CompareConfig::builder().tolerance(150).build();
+
+            assert_eq!(100_f32, config.get_tolerance());
+        }
+    }
+
+    mod get_compare_options_for {
+        use crate::{
+            config::{CompareConfig, PathOverride},
+            models::{ColourMetric, CompareOptions, Mask},
+        };
+
+        #[test]
+        fn applies_matching_overrides_in_order() {
+            let config = CompareConfig::builder()
+                .tolerance(10)
+                .masks([Mask::new(0, 0, 5, 5)])
+                .path_override(PathOverride {
+                    paths: vec!["checkout/**".to_string()],
+                    tolerance: Some(30),
+                    metric: Some(ColourMetric::Rgba),
+                    ..PathOverride::default()
+                })
+                .path_override(PathOverride {
+                    paths: vec!["**/cart.png".to_string()],
+                    threshold: Some(2.5),
+                    masks: Some(vec![]),
+                    ..PathOverride::default()
+                })
+                .build();
+
+            assert_eq!(
+                CompareOptions::new(30_f32)
+                    .with_metric(ColourMetric::Rgba)
+                    .with_threshold(2.5),
+                config.get_compare_options_for("images/latest/checkout/cart.png")
+            );
+            assert_eq!(
+                config.get_compare_options(),
+                config.get_compare_options_for("images/latest/home.png")
+            );
+        }
+    }
+
+    mod is_excluded {
+        use crate::config::{CompareConfig, PathOverride};
+
+        #[test]
+        fn when_path_is_not_included_or_matches_exclude_glob() {
+            let config = CompareConfig::builder()
+                .include(["checkout/**", "home/**"])
+                .exclude(["home/experimental/**"])
+                .build();
+
+            assert!(!config.is_excluded("images/latest/checkout/cart.png", "images/latest"));
+            assert!(!config.is_excluded("images/original/home/a.png", "images/original"));
+            assert!(config.is_excluded("images/latest/home/experimental/b.png", "images/latest"));
+            assert!(config.is_excluded("images/latest/about.png", "images/latest"));
+        }
+
+        #[test]
+        fn when_path_matches_ignoring_override() {
+            let config = CompareConfig::builder()
+                .path_override(PathOverride {
+                    paths: vec!["wip/**".to_string()],
+                    ignore: true,
+                    ..PathOverride::default()
+                })
+                .path_override(PathOverride {
+                    paths: vec!["home/**".to_string()],
+                    tolerance: Some(1),
+                    ..PathOverride::default()
+                })
+                .build();
+
+            assert!(config.is_excluded("images/original/wip/b.png", "images/original"));
+            assert!(!config.is_excluded("images/latest/home/c.png", "images/latest"));
+        }
+    }
+}
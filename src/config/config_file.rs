@@ -0,0 +1,377 @@
+use std::{fs, path::Path, str::FromStr};
+
+use log::LevelFilter;
+use serde::Deserialize;
+
+use crate::{
+    errors::{handling::create_invalid_config_error, ivc::IVCError},
+    models::{
+        ColourMetric, ColourPrecision, DiffFormat, DiffLayout, HighlightColour, Mask,
+        ProfileHandling,
+    },
+    utils::globs::create_glob_set,
+};
+
+use super::{CompareConfigBuilder, PathOverride, SUPPORTED_IMAGE_FORMATS};
+
+/// The config file discovered in the working directory when `--config` is not passed.
+pub const CONFIG_FILE_NAME: &str = "ivc.toml";
+
+/// Options read from a toml config file, each taking the place of the default for the matching
+/// cli option, along with overrides for images matching globs.
+///
+/// `ignore` is accepted in place of `exclude`.
+///
+/// ```toml
+/// tolerance = 10
+/// exclude = ["**/*.tmp.png"]
+///
+/// [[overrides]]
+/// paths = ["checkout/**"]
+/// metric = "rgba"
+/// threshold = 0.5
+/// masks = [{ x = 0, y = 0, width = 200, height = 40 }]
+/// ```
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub directory: Option<String>,
+    pub original_dir: Option<String>,
+    pub latest_dir: Option<String>,
+    pub output_dir: Option<String>,
+    pub markdown: Option<String>,
+    pub tolerance: Option<u8>,
+    pub log_level: Option<String>,
+    pub formats: Option<Vec<String>>,
+    pub match_across_formats: Option<bool>,
+    pub metric: Option<ColourMetric>,
+    pub precision: Option<ColourPrecision>,
+    pub profiles: Option<ProfileHandling>,
+    pub threshold: Option<f32>,
+    pub masks: Option<Vec<Mask>>,
+    pub include: Option<Vec<String>>,
+    #[serde(alias = "ignore")]
+    pub exclude: Option<Vec<String>>,
+    pub svg_scale: Option<f32>,
+    pub svg_dpi: Option<f32>,
+    pub save_svg_rasters: Option<bool>,
+    pub diff_format: Option<DiffFormat>,
+    pub diff_layout: Option<DiffLayout>,
+    pub diff_highlight: Option<HighlightColour>,
+    pub diff_fade: Option<f32>,
+    pub diff_outlines: Option<bool>,
+    pub blink: Option<bool>,
+    pub crops: Option<bool>,
+    #[serde(default)]
+    pub overrides: Vec<PathOverride>,
+}
+
+impl ConfigFile {
+    /// Reads the config file at the location, or `ivc.toml` in the working directory when no
+    /// location is passed. When there is no `ivc.toml` an empty config is returned.
+    pub fn load(location: Option<&str>) -> Result<ConfigFile, IVCError> {
+        let location = match location {
+            Some(location) => location,
+            None if Path::new(CONFIG_FILE_NAME).is_file() => CONFIG_FILE_NAME,
+            None => return Ok(ConfigFile::default()),
+        };
+
+        let contents = fs::read_to_string(location)
+            .map_err(|error| create_invalid_config_error(location, error.to_string()))?;
+
+        ConfigFile::parse(location, &contents)
+    }
+
+    /// Parses and validates the contents of the config file at the location.
+    pub fn parse(location: &str, contents: &str) -> Result<ConfigFile, IVCError> {
+        let config_file: ConfigFile = toml::from_str(contents).map_err(|error| {
+            let message = match error.span() {
+                Some(span) => format!(
+                    "line {}: {}",
+                    contents[..span.start].matches('\n').count() + 1,
+                    error.message()
+                ),
+                None => error.message().to_string(),
+            };
+            create_invalid_config_error(location, message)
+        })?;
+
+        config_file
+            .validate()
+            .map_err(|message| create_invalid_config_error(location, message))?;
+
+        Ok(config_file)
+    }
+
+    /// Sets every option from the config file on the builder.
+    pub fn apply_to(self, mut builder: CompareConfigBuilder) -> CompareConfigBuilder {
+        if let Some(directory) = self.directory {
+            builder = builder.directory(directory);
+        }
+        if let Some(original_dir) = self.original_dir {
+            builder = builder.original_dir(original_dir);
+        }
+        if let Some(latest_dir) = self.latest_dir {
+            builder = builder.latest_dir(latest_dir);
+        }
+        if let Some(output_dir) = self.output_dir {
+            builder = builder.output_dir(output_dir);
+        }
+        if let Some(markdown) = self.markdown {
+            builder = builder.markdown_file(markdown);
+        }
+        if let Some(tolerance) = self.tolerance {
+            builder = builder.tolerance(tolerance);
+        }
+        if let Some(log_level) = self
+            .log_level
+            .and_then(|log_level| LevelFilter::from_str(&log_level).ok())
+        {
+            builder = builder.log_level(log_level);
+        }
+        if let Some(formats) = self.formats {
+            builder = builder.image_extensions(formats);
+        }
+        if let Some(match_across_formats) = self.match_across_formats {
+            builder = builder.match_across_formats(match_across_formats);
+        }
+        if let Some(metric) = self.metric {
+            builder = builder.metric(metric);
+        }
+        if let Some(precision) = self.precision {
+            builder = builder.precision(precision);
+        }
+        if let Some(profiles) = self.profiles {
+            builder = builder.profiles(profiles);
+        }
+        if let Some(threshold) = self.threshold {
+            builder = builder.threshold(threshold);
+        }
+        if let Some(masks) = self.masks {
+            builder = builder.masks(masks);
+        }
+        if let Some(include) = self.include {
+            builder = builder.include(include);
+        }
+        if let Some(exclude) = self.exclude {
+            builder = builder.exclude(exclude);
+        }
+        if let Some(svg_scale) = self.svg_scale {
+            builder = builder.svg_scale(svg_scale);
+        }
+        if let Some(svg_dpi) = self.svg_dpi {
+            builder = builder.svg_dpi(svg_dpi);
+        }
+        if let Some(save_svg_rasters) = self.save_svg_rasters {
+            builder = builder.save_svg_rasters(save_svg_rasters);
+        }
+        if let Some(diff_format) = self.diff_format {
+            builder = builder.diff_format(diff_format);
+        }
+        if let Some(diff_layout) = self.diff_layout {
+            builder = builder.diff_layout(diff_layout);
+        }
+        if let Some(diff_highlight) = self.diff_highlight {
+            builder = builder.diff_highlight(diff_highlight);
+        }
+        if let Some(diff_fade) = self.diff_fade {
+            builder = builder.diff_fade(diff_fade);
+        }
+        if let Some(diff_outlines) = self.diff_outlines {
+            builder = builder.diff_outlines(diff_outlines);
+        }
+        if let Some(blink) = self.blink {
+            builder = builder.blink(blink);
+        }
+        if let Some(crops) = self.crops {
+            builder = builder.crops(crops);
+        }
+        for path_override in self.overrides {
+            builder = builder.path_override(path_override);
+        }
+
+        builder
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(log_level) = &self.log_level {
+            LevelFilter::from_str(log_level)
+                .map_err(|_| format!("unknown log_level '{log_level}'"))?;
+        }
+        for format in self.formats.iter().flatten() {
+            if !SUPPORTED_IMAGE_FORMATS.contains(&format.as_str()) {
+                return Err(format!("unsupported format '{format}'"));
+            }
+        }
+        validate_percentage("tolerance", self.tolerance.map(f32::from))?;
+        validate_percentage("threshold", self.threshold)?;
+        validate_positive("svg_scale", self.svg_scale)?;
+        validate_positive("svg_dpi", self.svg_dpi)?;
+        validate_percentage("diff_fade", self.diff_fade)?;
+        for globs in [&self.include, &self.exclude] {
+            create_glob_set(globs.as_deref().unwrap_or_default()).map_err(|err| err.to_string())?;
+        }
+
+        for path_override in self.overrides.iter() {
+            if path_override.paths.is_empty() {
+                return Err("every override needs at least one glob in paths".to_string());
+            }
+            create_glob_set(&path_override.paths).map_err(|err| err.to_string())?;
+            validate_percentage("tolerance", path_override.tolerance.map(f32::from))?;
+            validate_percentage("threshold", path_override.threshold)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_percentage(name: &str, value: Option<f32>) -> Result<(), String> {
+    match value {
+        Some(value) if !(0_f32..=100_f32).contains(&value) => {
+            Err(format!("{name} must be between 0 and 100, found '{value}'"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_positive(name: &str, value: Option<f32>) -> Result<(), String> {
+    match value {
+        Some(value) if !(value.is_finite() && value > 0_f32) => {
+            Err(format!("{name} must be above 0, found '{value}'"))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse {
+        mod returns_config_file {
+            use crate::{
+                config::{config_file::ConfigFile, CompareConfig, PathOverride},
+                models::{ColourMetric, DiffFormat, HighlightColour, Mask},
+            };
+
+            #[test]
+            fn when_global_options_and_overrides_are_valid() {
+                let contents = r##"
+                    directory = "screenshots"
+                    tolerance = 10
+                    formats = ["png", "webp"]
+                    metric = "lab"
+                    ignore = ["**/*.tmp.png"]
+                    diff_format = "webp"
+                    diff_highlight = "#00ff00"
+
+                    [[overrides]]
+                    paths = ["checkout/**"]
+                    threshold = 0.5
+                    masks = [{ x = 1, y = 2, width = 3, height = 4 }]
+
+                    [[overrides]]
+                    paths = ["wip/**"]
+                    ignore = true
+                "##;
+
+                let config_file = ConfigFile::parse("ivc.toml", contents).unwrap();
+
+                let expected = CompareConfig::builder()
+                    .directory("screenshots")
+                    .tolerance(10)
+                    .image_extensions(["png", "webp"])
+                    .metric(ColourMetric::Lab)
+                    .exclude(["**/*.tmp.png"])
+                    .diff_format(DiffFormat::Webp)
+                    .diff_highlight(HighlightColour([0, 255, 0]))
+                    .path_override(PathOverride {
+                        paths: vec!["checkout/**".to_string()],
+                        threshold: Some(0.5),
+                        masks: Some(vec![Mask::new(1, 2, 3, 4)]),
+                        ..PathOverride::default()
+                    })
+                    .path_override(PathOverride {
+                        paths: vec!["wip/**".to_string()],
+                        ignore: true,
+                        ..PathOverride::default()
+                    })
+                    .build();
+
+                assert_eq!(
+                    expected,
+                    config_file.apply_to(CompareConfig::builder()).build()
+                );
+            }
+        }
+
+        mod returns_error {
+            use crate::config::config_file::ConfigFile;
+
+            fn get_error_text(contents: &str) -> String {
+                ConfigFile::parse("ivc.toml", contents)
+                    .unwrap_err()
+                    .to_string()
+            }
+
+            #[test]
+            fn when_option_is_unknown() {
+                assert_eq!(
+                    "Invalid config file: 'ivc.toml'. Message: 'line 2: unknown field `tolerence`, expected one of `directory`, `original_dir`, `latest_dir`, `output_dir`, `markdown`, `tolerance`, `log_level`, `formats`, `match_across_formats`, `metric`, `precision`, `profiles`, `threshold`, `masks`, `include`, `exclude`, `ignore`, `svg_scale`, `svg_dpi`, `save_svg_rasters`, `diff_format`, `diff_layout`, `diff_highlight`, `diff_fade`, `diff_outlines`, `blink`, `crops`, `overrides`'",
+                    get_error_text("directory = \"images\"\ntolerence = 5")
+                );
+            }
+
+            #[test]
+            fn when_override_tolerance_is_out_of_range() {
+                assert_eq!(
+                    "Invalid config file: 'ivc.toml'. Message: 'tolerance must be between 0 and 100, found '150''",
+                    get_error_text("[[overrides]]\npaths = [\"a/**\"]\ntolerance = 150")
+                );
+            }
+
+            #[test]
+            fn when_override_glob_is_invalid() {
+                assert!(
+                    get_error_text("[[overrides]]\npaths = [\"a/[\"]").starts_with(
+                        "Invalid config file: 'ivc.toml'. Message: 'Invalid glob pattern: 'a/['."
+                    )
+                );
+            }
+
+            #[test]
+            fn when_format_is_unsupported() {
+                assert_eq!(
+                    "Invalid config file: 'ivc.toml'. Message: 'unsupported format 'pdf''",
+                    get_error_text("formats = [\"pdf\"]")
+                );
+            }
+
+            #[test]
+            fn when_svg_scale_is_not_above_zero() {
+                assert_eq!(
+                    "Invalid config file: 'ivc.toml'. Message: 'svg_scale must be above 0, found '0''",
+                    get_error_text("svg_scale = 0.0")
+                );
+            }
+
+            #[test]
+            fn when_diff_highlight_is_not_a_hex_colour() {
+                assert!(get_error_text("diff_highlight = \"red\"")
+                    .contains("'red' is not a hex colour, such as '#ff0000'"));
+            }
+        }
+    }
+
+    mod load {
+        use crate::config::config_file::ConfigFile;
+
+        #[test]
+        fn returns_error_when_passed_location_does_not_exist() {
+            let result = ConfigFile::load(Some("does/not/exist.toml"));
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid config file: 'does/not/exist.toml'. Message: 'No such file"));
+        }
+    }
+}
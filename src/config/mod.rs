@@ -1,85 +1,770 @@
-use std::{path::Path, str::FromStr};
+mod commands;
+mod compare_config;
+mod config_file;
+mod path_override;
+
+pub use commands::{
+    ApproveOptions, Command, ComparePairOptions, MergeReportsOptions, RollbackOptions, ServeOptions,
+};
+pub use compare_config::{CompareConfig, CompareConfigBuilder};
+pub use config_file::{ConfigFile, CONFIG_FILE_NAME};
+pub use path_override::PathOverride;
+
+use std::str::FromStr;
 
 use clap::{builder::PossibleValuesParser, Parser};
 use log::LevelFilter;
 
+use crate::{
+    errors::ivc::IVCError,
+    models::{
+        ColourMetric, ColourPrecision, DiffFormat, DiffLayout, HighlightColour, PlanFormat,
+        ProfileHandling, Shard,
+    },
+    utils::globs::create_glob_set,
+};
+
+pub const DEFAULT_TOLERANCE: u8 = 5;
+pub const DEFAULT_IMAGE_FORMAT: &str = "png";
+
+const SUPPORTED_IMAGE_FORMATS: [&str; 11] = [
+    "png", "jpg", "jpeg", "webp", "bmp", "tiff", "tif", "gif", "exr", "hdr", "svg",
+];
+
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(
+    version,
+    about,
+    after_help = "Options not passed are read from their IVC_* environment variable, then the config file, falling back to their defaults.\n\nExit codes: 0 when completed, 1 when failed, 3 when completed with mismatched images."
+)]
 pub struct AppConfig {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "IVC_CONFIG",
+        help = "config file to read options from [default: ivc.toml when present]",
+        long_help = "A toml config file setting any of the options, along with per-path overrides for images matching globs.\n\nDefaults to 'ivc.toml' in the directory the cli is run from, when present. Options passed to the cli, or set via their environment variable, take precedence over the config file."
+    )]
+    config: Option<String>,
+
     #[arg(
         short,
         long,
-        default_value = "images",
-        help = "directory containg images",
-        long_help = "The directory containing all the images.\n\nShould be a relative path from where the cli is run."
+        global = true,
+        env = "IVC_DIRECTORY",
+        help = "directory containg images [default: images]",
+        long_help = "The directory containing all the images.\n\nShould be a relative path from where the cli is run. Defaults to 'images'."
     )]
-    directory: String,
+    directory: Option<String>,
 
     #[arg(
       short,
       long,
-      default_value_t = 5,
+      global = true,
       value_parser = clap::value_parser!(u8).range(0..=100),
-      help = "tolerance for pixel difference (0 - 100)",
-      long_help = "When comparing an original and latest image, this is the desired tolerance for pixel difference (0 - 100).\n\nWill be checked via the squared distance between lab colours for each pixel pair.",
+      env = "IVC_TOLERANCE",
+      help = "tolerance for pixel difference (0 - 100) [default: 5]",
+      long_help = "When comparing an original and latest image, this is the desired tolerance for pixel difference (0 - 100).\n\nWill be checked via the squared distance between lab colours for each pixel pair, unless another --metric is chosen. Defaults to 5.",
     )]
-    tolerance: u8,
+    tolerance: Option<u8>,
 
     #[arg(
         short,
         long,
-        default_value = LevelFilter::Info.as_str(),
+        global = true,
         value_parser = PossibleValuesParser::new(
             LevelFilter::iter().map(|level| level.as_str())
         ),
-        long_help = "Will log events at the chosen level and below",
+        env = "IVC_LOG_LEVEL",
+        help = "level to log at [default: INFO]",
+        long_help = "Will log events at the chosen level and below. Defaults to INFO.",
+    )]
+    log_level: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "IVC_ORIGINAL_DIR",
+        help = "directory containing original images [default: <DIRECTORY>/original]",
+        long_help = "The directory containing the original images.\n\nCan be an absolute path, or a relative path from where the cli is run. Defaults to 'original' within --directory."
+    )]
+    original_dir: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "IVC_LATEST_DIR",
+        help = "directory containing latest images [default: <DIRECTORY>/latest]",
+        long_help = "The directory containing the latest images.\n\nCan be an absolute path, or a relative path from where the cli is run. Defaults to 'latest' within --directory."
+    )]
+    latest_dir: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "IVC_OUTPUT_DIR",
+        help = "directory mismatch images are written to [default: <DIRECTORY>/mismatched]",
+        long_help = "The directory mismatch images are written to, created if it does not exist.\n\nCan be an absolute path, or a relative path from where the cli is run. Defaults to 'mismatched' within --directory."
+    )]
+    output_dir: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        env = "IVC_MARKDOWN",
+        help = "write a markdown summary of the run to the path",
+        long_help = "Write a markdown summary of the run to the path, such as for posting as a pull request comment, alongside the results file and html report.\n\nThe summary has the totals of the run, a table of the mismatched pairs with their mismatch percentage, number of changed regions and a link to their diff image, relative to the summary, and any new or missing images."
+    )]
+    markdown: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(SUPPORTED_IMAGE_FORMATS),
+        env = "IVC_FORMATS",
+        help = "comma separated extensions of images to compare [default: png]",
+        long_help = "The extensions of the images to compare, separated by commas, e.g. 'png,jpg,webp'.\n\nOnly used to find the image files, each image is decoded based on its content, other than svg images which are rasterised first. 'jpg'/'jpeg' and 'tif'/'tiff' find either extension. Defaults to 'png'."
+    )]
+    formats: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        env = "IVC_MATCH_ACROSS_FORMATS",
+        help = "pair images whose names only differ by extension",
+        long_help = "Pair an original and latest image when their paths only differ by extension, e.g. 'foo.png' and 'foo.webp'.\n\nPassing '--match-across-formats=false' turns it off when set by the environment or config file."
+    )]
+    match_across_formats: Option<bool>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "IVC_METRIC",
+        help = "how pixel difference is measured [default: lab_squared]",
+        long_help = "How the difference between two pixels is measured before being checked against the tolerance. Defaults to 'lab_squared'."
+    )]
+    metric: Option<ColourMetric>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "IVC_PRECISION",
+        help = "precision pixels are compared at [default: eight_bit]",
+        long_help = "The precision pixels are compared at. With 'native', images with more than 8 bits per channel, such as 16-bit pngs and float images, are compared without first being converted to 8 bits per channel.\n\nDifferences are still measured on the 8-bit scale, so the tolerance means the same, but fractions of an 8-bit step are kept. The lab metrics clamp float channels to 0 - 1, the rgba metric also compares float channels above 1. Defaults to 'eight_bit'."
+    )]
+    precision: Option<ColourPrecision>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "IVC_PROFILES",
+        help = "how embedded icc colour profiles are handled [default: warn]",
+        long_help = "How the icc colour profiles embedded in png and jpeg images are handled, images without one being treated as sRGB.\n\nWith 'warn', pixels are compared as they are stored and a warning is logged for each pair whose profiles differ. With 'convert', images with a profile other than sRGB are converted to sRGB at 8 bits per channel before being compared. With 'ignore', profiles are not read. The profiles of each pair are recorded in the results file. Defaults to 'warn'."
+    )]
+    profiles: Option<ProfileHandling>,
+
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_percentage,
+        env = "IVC_THRESHOLD",
+        help = "percentage of pixels allowed to mismatch (0 - 100) [default: 0]",
+        long_help = "The percentage of pixels (0 - 100) that may be mismatched with a pair still being treated as matched. Defaults to 0."
+    )]
+    threshold: Option<f32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "GLOB",
+        env = "IVC_INCLUDE",
+        help = "only compare images matching the glob (repeatable)",
+        long_help = "Only compare images whose path, relative to the original or latest directory, matches the glob, e.g. 'checkout/**'.\n\nCan be passed multiple times. Images that are not included are left out before pairing, so are not counted as new or missing."
+    )]
+    include: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "GLOB",
+        env = "IVC_EXCLUDE",
+        help = "skip images matching the glob (repeatable)",
+        long_help = "Skip images whose path, relative to the original or latest directory, matches the glob, e.g. 'experimental/**', even when they match --include.\n\nCan be passed multiple times. Excluded images are left out before pairing, so are not counted as new or missing."
+    )]
+    exclude: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_positive_number,
+        env = "IVC_SVG_SCALE",
+        help = "scale svg images are rasterised at [default: 1]",
+        long_help = "How many pixels each svg user unit is rasterised to before comparing, e.g. '2' rasterises a 24x24 icon to 48x48 pixels. Defaults to 1."
+    )]
+    svg_scale: Option<f32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_positive_number,
+        env = "IVC_SVG_DPI",
+        help = "dpi svg physical units are converted at [default: 96]",
+        long_help = "The dpi svg lengths in physical units, such as 'mm' or 'in', are converted to pixels at, before --svg-scale is applied. Defaults to 96."
+    )]
+    svg_dpi: Option<f32>,
+
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        env = "IVC_SAVE_SVG_RASTERS",
+        help = "write rasterised svg images alongside the mismatch images",
+        long_help = "Write the rasterised original and latest image of each svg pair to the mismatched directory, next to where its mismatch image is written, e.g. 'icon.original.png' and 'icon.latest.png'.\n\nPassing '--save-svg-rasters=false' turns it off when set by the environment or config file."
+    )]
+    save_svg_rasters: Option<bool>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "IVC_DIFF_FORMAT",
+        help = "format diff images are written in [default: png]",
+        long_help = "The format diff images are written in, 'webp' being lossless and 'jpeg' being written without an alpha channel. Animated diffs are always written as gif. Defaults to 'png'."
+    )]
+    diff_format: Option<DiffFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "IVC_DIFF_LAYOUT",
+        help = "how the images of a pair are laid out in its diff image [default: diff_only]",
+        long_help = "How the images of a mismatched pair are laid out in its diff image.\n\nWith 'diff_only', the latest image with its mismatched pixels highlighted. With 'triptych', the original image, the highlighted latest image and the latest image side by side. With 'overlay', the original image's brightness in red and the latest image's in green, so that unchanged areas are yellow. With 'heatmap', each differing pixel coloured by how much it differs, as measured by --metric, from blue for the slightest difference to red for a black pixel becoming white, with a legend of the colour ramp beneath marking the tolerance. Defaults to 'diff_only'."
+    )]
+    diff_layout: Option<DiffLayout>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "HEX",
+        value_parser = HighlightColour::from_str,
+        env = "IVC_DIFF_HIGHLIGHT",
+        help = "colour mismatched pixels are highlighted in [default: #ff0000]",
+        long_help = "The hex colour mismatched pixels, and any region outlines, are drawn in on diff images, e.g. '#ff00ff'. Defaults to '#ff0000'."
+    )]
+    diff_highlight: Option<HighlightColour>,
+
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_percentage,
+        env = "IVC_DIFF_FADE",
+        help = "percentage unchanged pixels are faded towards white (0 - 100) [default: 0]",
+        long_help = "The percentage (0 - 100) the pixels of diff images that are not mismatched are faded towards white, so that the highlighted pixels stand out. Not used by the overlay layout. Defaults to 0."
     )]
-    log_level: String,
+    diff_fade: Option<f32>,
 
-    #[arg(skip = "latest")]
-    latest_images: String,
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        env = "IVC_DIFF_OUTLINES",
+        help = "outline each region of mismatched pixels in diff images",
+        long_help = "Draw an outline around each region of touching mismatched pixels in diff images, so that small changes are easy to find.\n\nPassing '--diff-outlines=false' turns it off when set by the environment or config file."
+    )]
+    diff_outlines: Option<bool>,
 
-    #[arg(skip = "original")]
-    original_images: String,
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        env = "IVC_BLINK",
+        help = "write a gif alternating the original and latest image of each mismatched pair",
+        long_help = "Write an animated gif for each mismatched pair that alternates between its original and latest image every half second, with each region of mismatched pixels outlined, next to its diff image, e.g. 'home.blink.gif'. Its location is recorded in the results file.\n\nPassing '--blink=false' turns it off when set by the environment or config file."
+    )]
+    blink: Option<bool>,
 
-    #[arg(skip = "mismatched")]
-    mismatched_images: String,
+    #[arg(
+        long,
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+        env = "IVC_CROPS",
+        help = "write zoomed crops of each changed region of mismatched pairs",
+        long_help = "Write zoomed crops of the original, latest and diff image for each region of mismatched pixels, up to the 20 largest, next to the diff image of still pairs, e.g. 'home.region1.original.png'. Each crop keeps a few pixels around its region and is scaled up without smoothing. Their locations are recorded in the results file.\n\nPassing '--crops=false' turns it off when set by the environment or config file."
+    )]
+    crops: Option<bool>,
 
-    #[arg(skip = "png")]
-    pub image_extension: String,
+    #[arg(
+        long,
+        global = true,
+        value_name = "I/N",
+        value_parser = Shard::from_str,
+        env = "IVC_SHARD",
+        help = "only compare the i-th of n shards of the images, e.g. 2/4",
+        long_help = "Split the images into n shards by a hash of their relative path and only compare the i-th, e.g. '2/4', so that a run can be spread across CI jobs.\n\nEach shard writes its own results file and html report, which can be combined with the merge-reports subcommand."
+    )]
+    shard: Option<Shard>,
+
+    #[arg(
+        long,
+        env = "IVC_WATCH",
+        help = "re-compare images whenever the latest images change",
+        long_help = "After comparing, keep watching the latest directory and re-compare only the pairs whose latest image changed, refreshing the results file and html report.\n\nUses file system notifications, falling back to polling where they are unavailable. Runs until stopped."
+    )]
+    watch: bool,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "text",
+        value_name = "FORMAT",
+        value_enum,
+        env = "IVC_DRY_RUN",
+        conflicts_with = "watch",
+        help = "list the pairs that would be compared, without comparing them",
+        long_help = "Find and pair the images as a run would, then output the planned pairs, new and missing images and filtered files, without decoding any images or writing any files.\n\nPassing '--dry-run=json' writes the plan as json, with nothing else written to stdout."
+    )]
+    dry_run: Option<PlanFormat>,
 }
 
-// TODO: test
 impl AppConfig {
-    pub fn get_tolerance(&self) -> f32 {
-        self.tolerance as f32
+    /// Takes the subcommand that was passed, `None` meaning the images should be compared.
+    pub fn take_command(&mut self) -> Option<Command> {
+        self.command.take()
     }
 
-    pub fn get_log_level(&self) -> LevelFilter {
-        match LevelFilter::from_str(&self.log_level) {
-            Ok(level) => level,
-            _ => LevelFilter::Info,
-        }
+    pub fn is_watching(&self) -> bool {
+        self.watch
     }
 
-    pub fn get_original_images_dir(&self) -> String {
-        Path::new(&self.directory)
-            .join(&self.original_images)
-            .to_string_lossy()
-            .to_string()
+    /// The format to output the run's plan in, `None` meaning the images should be compared.
+    pub fn get_dry_run(&self) -> Option<PlanFormat> {
+        self.dry_run
     }
 
-    pub fn get_latest_images_dir(&self) -> String {
-        Path::new(&self.directory)
-            .join(&self.latest_images)
-            .to_string_lossy()
-            .to_string()
+    fn get_log_level(&self) -> Option<LevelFilter> {
+        self.log_level
+            .as_ref()
+            .and_then(|log_level| LevelFilter::from_str(log_level).ok())
     }
+}
+
+fn parse_percentage(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(percentage) if (0_f32..=100_f32).contains(&percentage) => Ok(percentage),
+        _ => Err(format!("'{value}' is not a number between 0 and 100")),
+    }
+}
+
+fn parse_positive_number(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(number) if number.is_finite() && number > 0_f32 => Ok(number),
+        _ => Err(format!("'{value}' is not a number above 0")),
+    }
+}
+
+/// Reads the config file, then sets any option passed to the cli or set via its environment
+/// variable on top of it, so that they take precedence.
+impl TryFrom<AppConfig> for CompareConfig {
+    type Error = IVCError;
+
+    fn try_from(app_config: AppConfig) -> Result<Self, Self::Error> {
+        let config_file = ConfigFile::load(app_config.config.as_deref())?;
+        let mut builder = config_file.apply_to(CompareConfig::builder());
+
+        if let Some(log_level) = app_config.get_log_level() {
+            builder = builder.log_level(log_level);
+        }
+        if let Some(directory) = app_config.directory {
+            builder = builder.directory(directory);
+        }
+        if let Some(tolerance) = app_config.tolerance {
+            builder = builder.tolerance(tolerance);
+        }
+        if let Some(formats) = app_config.formats {
+            builder = builder.image_extensions(formats);
+        }
+        if let Some(match_across_formats) = app_config.match_across_formats {
+            builder = builder.match_across_formats(match_across_formats);
+        }
+        if let Some(original_dir) = app_config.original_dir {
+            builder = builder.original_dir(original_dir);
+        }
+        if let Some(latest_dir) = app_config.latest_dir {
+            builder = builder.latest_dir(latest_dir);
+        }
+        if let Some(output_dir) = app_config.output_dir {
+            builder = builder.output_dir(output_dir);
+        }
+        if let Some(markdown) = app_config.markdown {
+            builder = builder.markdown_file(markdown);
+        }
+        if let Some(metric) = app_config.metric {
+            builder = builder.metric(metric);
+        }
+        if let Some(precision) = app_config.precision {
+            builder = builder.precision(precision);
+        }
+        if let Some(profiles) = app_config.profiles {
+            builder = builder.profiles(profiles);
+        }
+        if let Some(threshold) = app_config.threshold {
+            builder = builder.threshold(threshold);
+        }
+        if let Some(include) = app_config.include {
+            create_glob_set(&include)?;
+            builder = builder.include(include);
+        }
+        if let Some(exclude) = app_config.exclude {
+            create_glob_set(&exclude)?;
+            builder = builder.exclude(exclude);
+        }
+        if let Some(svg_scale) = app_config.svg_scale {
+            builder = builder.svg_scale(svg_scale);
+        }
+        if let Some(svg_dpi) = app_config.svg_dpi {
+            builder = builder.svg_dpi(svg_dpi);
+        }
+        if let Some(save_svg_rasters) = app_config.save_svg_rasters {
+            builder = builder.save_svg_rasters(save_svg_rasters);
+        }
+        if let Some(diff_format) = app_config.diff_format {
+            builder = builder.diff_format(diff_format);
+        }
+        if let Some(diff_layout) = app_config.diff_layout {
+            builder = builder.diff_layout(diff_layout);
+        }
+        if let Some(diff_highlight) = app_config.diff_highlight {
+            builder = builder.diff_highlight(diff_highlight);
+        }
+        if let Some(diff_fade) = app_config.diff_fade {
+            builder = builder.diff_fade(diff_fade);
+        }
+        if let Some(diff_outlines) = app_config.diff_outlines {
+            builder = builder.diff_outlines(diff_outlines);
+        }
+        if let Some(blink) = app_config.blink {
+            builder = builder.blink(blink);
+        }
+        if let Some(crops) = app_config.crops {
+            builder = builder.crops(crops);
+        }
+        if let Some(shard) = app_config.shard {
+            builder = builder.shard(shard);
+        }
 
-    pub fn get_mismatched_images_dir(&self) -> String {
-        Path::new(&self.directory)
-            .join(&self.mismatched_images)
-            .to_string_lossy()
-            .to_string()
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_app_config {
+        use std::{
+            env,
+            ffi::OsString,
+            fs,
+            sync::{Mutex, MutexGuard},
+        };
+
+        use clap::Parser;
+        use log::LevelFilter;
+
+        use crate::{
+            config::{AppConfig, ApproveOptions, Command, CompareConfig},
+            models::{
+                ColourMetric, ColourPrecision, DiffFormat, DiffLayout, HighlightColour, PlanFormat,
+                ProfileHandling, Shard,
+            },
+            test_utils::files::create_temp_dir_handler,
+        };
+
+        // cli options can be read from environment variables, which are shared by every test
+        static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+        fn lock_env() -> MutexGuard<'static, ()> {
+            ENV_LOCK
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+
+        #[test]
+        fn carries_over_parsed_cli_options() {
+            let _env_guard = lock_env();
+            let app_config = AppConfig::parse_from(
+                [
+                    "ivc",
+                    "--directory",
+                    "screenshots",
+                    "--tolerance",
+                    "12",
+                    "--log-level",
+                    "DEBUG",
+                    "--original-dir",
+                    "/baselines",
+                    "--latest-dir",
+                    "captures/latest",
+                    "--output-dir",
+                    "/tmp/diffs",
+                    "--markdown",
+                    "/tmp/summary.md",
+                    "--formats",
+                    "png,jpg,webp",
+                    "--match-across-formats",
+                    "--metric",
+                    "lab",
+                    "--precision",
+                    "native",
+                    "--profiles",
+                    "convert",
+                    "--threshold",
+                    "1.5",
+                    "--include",
+                    "checkout/**",
+                    "--include",
+                    "home/**",
+                    "--exclude",
+                    "**/experimental/**",
+                    "--svg-scale",
+                    "2",
+                    "--svg-dpi",
+                    "72",
+                    "--save-svg-rasters",
+                    "--diff-format",
+                    "jpeg",
+                    "--diff-layout",
+                    "triptych",
+                    "--diff-highlight",
+                    "#00ffff",
+                    "--diff-fade",
+                    "40",
+                    "--diff-outlines",
+                    "--blink",
+                    "--crops",
+                ]
+                .map(OsString::from),
+            );
+
+            let expected = CompareConfig::builder()
+                .directory("screenshots")
+                .tolerance(12)
+                .log_level(LevelFilter::Debug)
+                .original_dir("/baselines")
+                .latest_dir("captures/latest")
+                .output_dir("/tmp/diffs")
+                .markdown_file("/tmp/summary.md")
+                .image_extensions(["png", "jpg", "webp"])
+                .match_across_formats(true)
+                .metric(ColourMetric::Lab)
+                .precision(ColourPrecision::Native)
+                .profiles(ProfileHandling::Convert)
+                .threshold(1.5)
+                .include(["checkout/**", "home/**"])
+                .exclude(["**/experimental/**"])
+                .svg_scale(2_f32)
+                .svg_dpi(72_f32)
+                .save_svg_rasters(true)
+                .diff_format(DiffFormat::Jpeg)
+                .diff_layout(DiffLayout::Triptych)
+                .diff_highlight(HighlightColour([0, 255, 255]))
+                .diff_fade(40_f32)
+                .diff_outlines(true)
+                .blink(true)
+                .crops(true)
+                .build();
+
+            assert_eq!(expected, CompareConfig::try_from(app_config).unwrap());
+        }
+
+        #[test]
+        fn carries_over_options_passed_after_a_subcommand() {
+            let _env_guard = lock_env();
+            let mut app_config = AppConfig::parse_from(
+                [
+                    "ivc",
+                    "approve",
+                    "--directory",
+                    "screenshots",
+                    "--only",
+                    "checkout/**",
+                    "--remove-deleted",
+                ]
+                .map(OsString::from),
+            );
+
+            assert_eq!(
+                Some(Command::Approve(ApproveOptions {
+                    only: vec!["checkout/**".to_string()],
+                    remove_deleted: true,
+                })),
+                app_config.take_command()
+            );
+            assert_eq!(
+                CompareConfig::builder().directory("screenshots").build(),
+                CompareConfig::try_from(app_config).unwrap()
+            );
+        }
+
+        #[test]
+        fn matches_builder_defaults_when_no_cli_options_are_passed() {
+            let _env_guard = lock_env();
+            let app_config = AppConfig::parse_from(["ivc"].map(OsString::from));
+
+            assert_eq!(
+                CompareConfig::builder().build(),
+                CompareConfig::try_from(app_config).unwrap()
+            );
+        }
+
+        #[test]
+        fn uses_config_file_with_cli_options_taking_precedence() {
+            let _env_guard = lock_env();
+            let temp_dir_handler = create_temp_dir_handler();
+            let config_location = temp_dir_handler.get_location_of_file_name("ivc.toml");
+            fs::write(
+                &config_location,
+                "directory = \"screenshots\"\ntolerance = 20\nmetric = \"rgba\"\nignore = [\"wip/**\"]\n",
+            )
+            .unwrap();
+
+            let app_config = AppConfig::parse_from(
+                ["ivc", "--config", &config_location, "--tolerance", "3"].map(OsString::from),
+            );
+
+            let expected = CompareConfig::builder()
+                .directory("screenshots")
+                .tolerance(3)
+                .metric(ColourMetric::Rgba)
+                .exclude(["wip/**"])
+                .build();
+
+            assert_eq!(expected, CompareConfig::try_from(app_config).unwrap());
+        }
+
+        #[test]
+        fn returns_error_when_config_file_is_invalid() {
+            let _env_guard = lock_env();
+            let temp_dir_handler = create_temp_dir_handler();
+            let config_location = temp_dir_handler.get_location_of_file_name("ivc.toml");
+            fs::write(&config_location, "tolerance = \"high\"").unwrap();
+
+            let app_config =
+                AppConfig::parse_from(["ivc", "--config", &config_location].map(OsString::from));
+
+            assert!(CompareConfig::try_from(app_config)
+                .unwrap_err()
+                .to_string()
+                .starts_with(&format!(
+                    "Invalid config file: '{config_location}'. Message: 'line 1:"
+                )));
+        }
+
+        #[test]
+        fn uses_environment_variables_over_config_file_but_not_over_cli() {
+            let _env_guard = lock_env();
+            let temp_dir_handler = create_temp_dir_handler();
+            let config_location = temp_dir_handler.get_location_of_file_name("ivc.toml");
+            fs::write(
+                &config_location,
+                "directory = \"screenshots\"\ntolerance = 20\nthreshold = 1.0\nmatch_across_formats = true\n",
+            )
+            .unwrap();
+            let variables = [
+                ("IVC_CONFIG", config_location.as_str()),
+                ("IVC_DIRECTORY", "from_env"),
+                ("IVC_TOLERANCE", "40"),
+                ("IVC_METRIC", "rgba"),
+                ("IVC_MATCH_ACROSS_FORMATS", "false"),
+            ];
+            for (name, value) in variables {
+                env::set_var(name, value);
+            }
+
+            let app_config =
+                AppConfig::parse_from(["ivc", "--directory", "from_cli"].map(OsString::from));
+            let result = CompareConfig::try_from(app_config);
+
+            for (name, _) in variables {
+                env::remove_var(name);
+            }
+
+            let expected = CompareConfig::builder()
+                .directory("from_cli")
+                .tolerance(40)
+                .metric(ColourMetric::Rgba)
+                .threshold(1.0)
+                .match_across_formats(false)
+                .build();
+
+            assert_eq!(expected, result.unwrap());
+        }
+
+        #[test]
+        fn carries_over_shard_passed_before_a_subcommand() {
+            let _env_guard = lock_env();
+            let app_config = AppConfig::parse_from(
+                ["ivc", "--shard", "2/4", "merge-reports"].map(OsString::from),
+            );
+
+            let config = CompareConfig::try_from(app_config).unwrap();
+
+            assert_eq!(Some(Shard::new(2, 4).unwrap()), config.get_shard());
+        }
+
+        #[test]
+        fn parses_dry_run_with_text_as_its_default_format() {
+            let _env_guard = lock_env();
+
+            let text = AppConfig::parse_from(["ivc", "--dry-run"].map(OsString::from));
+            let json = AppConfig::parse_from(["ivc", "--dry-run=json"].map(OsString::from));
+            let none = AppConfig::parse_from(["ivc"].map(OsString::from));
+
+            assert_eq!(Some(PlanFormat::Text), text.get_dry_run());
+            assert_eq!(Some(PlanFormat::Json), json.get_dry_run());
+            assert_eq!(None, none.get_dry_run());
+        }
+
+        #[test]
+        fn rejects_invalid_shard() {
+            let _env_guard = lock_env();
+            for shard in ["0/4", "5/4", "2", "a/b"] {
+                let result = AppConfig::try_parse_from(["ivc", "--shard", shard]);
+
+                assert!(result.is_err(), "'{shard}' should be rejected");
+            }
+        }
+
+        #[test]
+        fn returns_error_when_include_glob_is_invalid() {
+            let _env_guard = lock_env();
+            let app_config =
+                AppConfig::parse_from(["ivc", "--include", "checkout/["].map(OsString::from));
+
+            assert!(CompareConfig::try_from(app_config)
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid glob pattern: 'checkout/['."));
+        }
     }
 }
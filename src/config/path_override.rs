@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use crate::models::{ColourMetric, ColourPrecision, Mask};
+
+/// Options for the images whose path, relative to the original or latest directory, matches any
+/// of the globs. Any option that is not set keeps its global value.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathOverride {
+    pub paths: Vec<String>,
+    pub tolerance: Option<u8>,
+    pub metric: Option<ColourMetric>,
+    pub precision: Option<ColourPrecision>,
+    pub threshold: Option<f32>,
+    pub masks: Option<Vec<Mask>>,
+    /// Whether the matching images are left out of the comparison entirely.
+    #[serde(default)]
+    pub ignore: bool,
+}
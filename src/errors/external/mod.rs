@@ -34,3 +34,67 @@ impl TokioJoinError {
         }
     }
 }
+
+#[derive(Error, Debug)]
+#[error("Issue writing file at location: '{location}'. Message: '{source_message}'")]
+pub struct IOWriteError {
+    location: String,
+    source_message: String,
+}
+
+impl IOWriteError {
+    pub fn new(location: String, source: ImageError) -> Self {
+        IOWriteError {
+            location,
+            source_message: source.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Issue with JSON file at location: '{location}'. Message: '{source_message}'")]
+pub struct JsonFileError {
+    location: String,
+    source_message: String,
+}
+
+impl JsonFileError {
+    pub fn new(location: String, source: serde_json::Error) -> Self {
+        JsonFileError {
+            location,
+            source_message: source.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Issue watching directory: '{location}'. Message: '{source_message}'")]
+pub struct WatchError {
+    location: String,
+    source_message: String,
+}
+
+impl WatchError {
+    pub fn new(location: String, source: notify::Error) -> Self {
+        WatchError {
+            location,
+            source_message: source.to_string(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Issue serving review at address: '{address}'. Message: '{source_message}'")]
+pub struct ServeError {
+    address: String,
+    source_message: String,
+}
+
+impl ServeError {
+    pub fn new(address: String, source: std::io::Error) -> Self {
+        ServeError {
+            address,
+            source_message: source.to_string(),
+        }
+    }
+}
@@ -6,10 +6,12 @@ use tokio::task::JoinError;
 use crate::models::ImageHolder;
 
 use super::{
-    external::{IOReadError, TokioJoinError},
+    external::{IOReadError, IOWriteError, JsonFileError, ServeError, TokioJoinError, WatchError},
     internal::{
         ImageCountMismatchError, ImageNotPairedError, ImagePairDimensionMismatchError,
-        MissingDirectoriesError,
+        ImagePairPageCountMismatchError, InvalidConfigError, InvalidGlobError,
+        MissingDirectoriesError, NoShardResultsError, OverlappingDirectoriesError,
+        SnapshotNotFoundError,
     },
     ivc::IVCError,
 };
@@ -33,6 +35,16 @@ pub fn create_missing_directories_error(
     IVCError::MissingDirectory(MissingDirectoriesError::new(missing_directories))
 }
 
+pub fn create_overlapping_directories_error(
+    directory_one: String,
+    directory_two: String,
+) -> IVCError {
+    IVCError::OverlappingDirectories(OverlappingDirectoriesError::new(
+        directory_one,
+        directory_two,
+    ))
+}
+
 pub fn create_image_count_mismatch_error(original_count: usize, latest_count: usize) -> IVCError {
     IVCError::ImageCountMismatch(ImageCountMismatchError::new(original_count, latest_count))
 }
@@ -45,6 +57,10 @@ pub fn create_io_read_error(location: String, source: ImageError) -> IVCError {
     IVCError::IORead(IOReadError::new(location, source))
 }
 
+pub fn create_io_write_error(location: String, source: ImageError) -> IVCError {
+    IVCError::IOWrite(IOWriteError::new(location, source))
+}
+
 pub fn create_tokio_join_error(action: &str, source: JoinError) -> IVCError {
     IVCError::TokioJoin(TokioJoinError::new(action.to_owned(), source))
 }
@@ -55,3 +71,48 @@ pub fn create_dimension_mismatch_error(images: (ImageHolder, ImageHolder)) -> IV
         images.1.location.to_string(),
     ))
 }
+
+pub fn create_page_count_mismatch_error(
+    location_one: &str,
+    page_count_one: usize,
+    location_two: &str,
+    page_count_two: usize,
+) -> IVCError {
+    IVCError::ImagePairPageCountMismatch(ImagePairPageCountMismatchError::new(
+        location_one.to_owned(),
+        page_count_one,
+        location_two.to_owned(),
+        page_count_two,
+    ))
+}
+
+pub fn create_json_file_error(location: String, source: serde_json::Error) -> IVCError {
+    IVCError::JsonFile(JsonFileError::new(location, source))
+}
+
+pub fn create_invalid_glob_error(pattern: &str, source: globset::Error) -> IVCError {
+    IVCError::InvalidGlob(InvalidGlobError::new(
+        pattern.to_owned(),
+        source.kind().to_string(),
+    ))
+}
+
+pub fn create_snapshot_not_found_error(snapshot: &str) -> IVCError {
+    IVCError::SnapshotNotFound(SnapshotNotFoundError::new(snapshot.to_owned()))
+}
+
+pub fn create_watch_error(location: &str, source: notify::Error) -> IVCError {
+    IVCError::Watch(WatchError::new(location.to_owned(), source))
+}
+
+pub fn create_serve_error(address: &str, source: std::io::Error) -> IVCError {
+    IVCError::Serve(ServeError::new(address.to_owned(), source))
+}
+
+pub fn create_invalid_config_error(location: &str, message: String) -> IVCError {
+    IVCError::InvalidConfig(InvalidConfigError::new(location.to_owned(), message))
+}
+
+pub fn create_no_shard_results_error(directory: &str) -> IVCError {
+    IVCError::NoShardResults(NoShardResultsError::new(directory.to_owned()))
+}
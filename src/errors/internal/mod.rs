@@ -32,6 +32,31 @@ impl ImagePairDimensionMismatchError {
     }
 }
 
+#[derive(Error, Debug)]
+#[error("Number of pages in multi-page images do not match: '{location_one}' has '{page_count_one}', '{location_two}' has '{page_count_two}'.")]
+pub struct ImagePairPageCountMismatchError {
+    location_one: String,
+    page_count_one: usize,
+    location_two: String,
+    page_count_two: usize,
+}
+
+impl ImagePairPageCountMismatchError {
+    pub fn new(
+        location_one: String,
+        page_count_one: usize,
+        location_two: String,
+        page_count_two: usize,
+    ) -> Self {
+        ImagePairPageCountMismatchError {
+            location_one,
+            page_count_one,
+            location_two,
+            page_count_two,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[error("Number of images in original and latest directories do not match. Original: '{original_count}', Latest: '{latest_count}'.")]
 pub struct ImageCountMismatchError {
@@ -63,3 +88,71 @@ impl ImageNotPairedError {
         ImageNotPairedError {}
     }
 }
+
+#[derive(Error, Debug)]
+#[error(
+    "Directories must not be nested within each other: '{directory_one}' and '{directory_two}'."
+)]
+pub struct OverlappingDirectoriesError {
+    directory_one: String,
+    directory_two: String,
+}
+
+impl OverlappingDirectoriesError {
+    pub fn new(directory_one: String, directory_two: String) -> Self {
+        OverlappingDirectoriesError {
+            directory_one,
+            directory_two,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid glob pattern: '{pattern}'. Message: '{message}'")]
+pub struct InvalidGlobError {
+    pattern: String,
+    message: String,
+}
+
+impl InvalidGlobError {
+    pub fn new(pattern: String, message: String) -> Self {
+        InvalidGlobError { pattern, message }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Could not find baseline snapshot: '{snapshot}'.")]
+pub struct SnapshotNotFoundError {
+    snapshot: String,
+}
+
+impl SnapshotNotFoundError {
+    pub fn new(snapshot: String) -> Self {
+        SnapshotNotFoundError { snapshot }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid config file: '{location}'. Message: '{message}'")]
+pub struct InvalidConfigError {
+    location: String,
+    message: String,
+}
+
+impl InvalidConfigError {
+    pub fn new(location: String, message: String) -> Self {
+        InvalidConfigError { location, message }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Could not find any shard results to merge in directory: '{directory}'.")]
+pub struct NoShardResultsError {
+    directory: String,
+}
+
+impl NoShardResultsError {
+    pub fn new(directory: String) -> Self {
+        NoShardResultsError { directory }
+    }
+}
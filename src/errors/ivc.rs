@@ -1,31 +1,55 @@
-use std::fmt;
+use thiserror::Error;
 
 use super::external::IOReadError;
+use super::external::IOWriteError;
+use super::external::JsonFileError;
+use super::external::ServeError;
 use super::external::TokioJoinError;
+use super::external::WatchError;
 use super::internal::ImageCountMismatchError;
 use super::internal::ImageNotPairedError;
 use super::internal::ImagePairDimensionMismatchError;
+use super::internal::ImagePairPageCountMismatchError;
+use super::internal::InvalidConfigError;
+use super::internal::InvalidGlobError;
 use super::internal::MissingDirectoriesError;
+use super::internal::NoShardResultsError;
+use super::internal::OverlappingDirectoriesError;
+use super::internal::SnapshotNotFoundError;
 
-#[derive(Debug)]
+/// The errors of the crate, each displayed and sourced as the error it wraps.
+#[derive(Error, Debug)]
 pub enum IVCError {
+    #[error(transparent)]
     ImagePairDimensionMismatch(ImagePairDimensionMismatchError),
+    #[error(transparent)]
+    ImagePairPageCountMismatch(ImagePairPageCountMismatchError),
+    #[error(transparent)]
     IORead(IOReadError),
+    #[error(transparent)]
+    IOWrite(IOWriteError),
+    #[error(transparent)]
     MissingDirectory(MissingDirectoriesError),
+    #[error(transparent)]
+    OverlappingDirectories(OverlappingDirectoriesError),
+    #[error(transparent)]
     ImageCountMismatch(ImageCountMismatchError),
+    #[error(transparent)]
     ImageNotPaired(ImageNotPairedError),
+    #[error(transparent)]
     TokioJoin(TokioJoinError),
-}
-
-impl fmt::Display for IVCError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            IVCError::ImagePairDimensionMismatch(err) => err.fmt(f),
-            IVCError::IORead(err) => err.fmt(f),
-            IVCError::MissingDirectory(err) => err.fmt(f),
-            IVCError::ImageCountMismatch(err) => err.fmt(f),
-            IVCError::ImageNotPaired(err) => err.fmt(f),
-            IVCError::TokioJoin(err) => err.fmt(f),
-        }
-    }
+    #[error(transparent)]
+    JsonFile(JsonFileError),
+    #[error(transparent)]
+    InvalidGlob(InvalidGlobError),
+    #[error(transparent)]
+    SnapshotNotFound(SnapshotNotFoundError),
+    #[error(transparent)]
+    Watch(WatchError),
+    #[error(transparent)]
+    Serve(ServeError),
+    #[error(transparent)]
+    InvalidConfig(InvalidConfigError),
+    #[error(transparent)]
+    NoShardResults(NoShardResultsError),
 }
@@ -1,109 +1,93 @@
+mod approve;
 mod compare;
 mod errors;
+mod merge;
 mod models;
+mod plan;
+mod rollback;
+mod serve;
+mod stream;
 mod utils;
+mod watch;
 
 pub mod config;
+pub use crate::approve::approve;
+pub use crate::compare::{compare_image_files, compare_images};
+pub use crate::errors::ivc::IVCError;
+pub use crate::merge::merge_reports;
+pub use crate::models::{
+    AnimationFrame, AnimationResult, BaselineChange, ColourMetric, ColourPrecision, ColourProfile,
+    CompareOptions, ComparisonEvent, ComparisonOutcome, ComparisonResult, DiffFormat, DiffLayout,
+    DiffOptions, FrameResult, HighlightColour, ImageHolder, Mask, MismatchRegion, PairResult,
+    PairStatus, PixelCoord, PlanFormat, PlannedPair, ProfileHandling, ProfileResult, RegionCrop,
+    ReviewDecision, RunPlan, RunResults, Shard, SnapshotEntry, SnapshotManifest, SvgOptions,
+};
+pub use crate::plan::plan;
+pub use crate::rollback::{list_snapshots, rollback};
+pub use crate::serve::serve;
 pub use crate::utils::logger;
+pub use crate::watch::watch;
 
 mod test_utils;
 
-use models::{ComparisonResult, ImageHolder, PixelCoord};
-use tokio::runtime::Runtime;
-use tokio::task::JoinSet;
-
-use compare::compare_pair_of_images;
-use config::AppConfig;
-use errors::{
-    handling::{create_dimension_mismatch_error, create_tokio_join_error},
-    ivc::IVCError,
-};
-use utils::{
-    file_paths::get_file_path_pairs_if_valid,
-    file_system::{
-        directories::get_directories_if_exist, files::get_files_if_directories_match_in_file_count,
-        images::get_pair_of_images_from_file_locations,
-    },
-    image::are_dimensions_matching_for_images,
-};
-
-pub fn run(config: AppConfig) -> Result<(), IVCError> {
-    let pixel_tolerance = config.get_tolerance();
+use std::thread;
 
-    // TODO: should be able to return Err, and main invoking run should exit code 1
-    // TODO: test
-    let (original_dir, latest_dir) = get_directories_if_exist(&config)?;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{self, Receiver};
 
-    // TODO: should be able to return Err, and main invoking run should exit code 1
-    // TODO: test
-    let (orig_image_file_paths, latest_images_file_paths) =
-        get_files_if_directories_match_in_file_count(&config, original_dir, latest_dir)?;
+use config::CompareConfig;
+use logger::Logger;
+use stream::send_comparison_events;
+use utils::file_system::results::write_run_outputs;
 
-    // TODO: should be able to return Err, and main invoking run should exit code 1
-    // TODO: test
-    let image_pairs =
-        get_file_path_pairs_if_valid(&config, orig_image_file_paths, latest_images_file_paths)?;
+const EVENT_CHANNEL_CAPACITY: usize = 64;
 
-    let rt = Runtime::new().unwrap();
+/// Compares every image pair found via the config, stopping at the first failure.
+///
+/// The results of the run are written to the results file and html report in the mismatched
+/// directory, including when the run fails, so that any new or missing images are recorded.
+pub fn run(config: impl Into<CompareConfig>) -> Result<RunResults, IVCError> {
+    let config = config.into();
 
-    rt.block_on(async {
-        let mut retrieve_images_set = JoinSet::new();
+    let mut receiver = stream(config.clone());
+    let mut run_results = RunResults::default();
 
-        // TODO FUTURE: perhaps create a tokio message channel to send result of a spawned task
-        //          can then go through and check for errors etc.
-        // TODO FUTURE: also currently no return from this part so mismatch error goes nowhere
+    while let Some(event) = receiver.blocking_recv() {
+        Logger::debug(format!("comparison event: '{:?}'", event));
 
-        for (orig_image_location, lat_image_location) in image_pairs.into_iter() {
-            retrieve_images_set.spawn_blocking(move || {
-                get_pair_of_images_from_file_locations(&orig_image_location, &lat_image_location)
-            });
+        if let ComparisonEvent::Failed(err) = event {
+            drop(receiver);
+            run_results.sort();
+            if let Err(write_err) = write_run_outputs(&config, &run_results) {
+                Logger::error(write_err.to_string());
+            }
+            return Err(err);
         }
 
-        let mut create_mismatch_images_set = JoinSet::new();
-
-        while let Some(tokio_join_result) = retrieve_images_set.join_next().await {
-            let task_result = match tokio_join_result {
-                Ok(result) => result,
-                Err(err) => {
-                    return Err(create_tokio_join_error(
-                        "retrieving images from file system",
-                        err,
-                    ));
-                }
-            };
-
-            let image_pair = match task_result {
-                Ok(value) => value,
-                Err(err) => return Err(err),
-            };
-
-            if !are_dimensions_matching_for_images(&image_pair) {
-                return Err(create_dimension_mismatch_error(image_pair));
-            }
+        run_results.record_event(&event);
+    }
 
-            let mismatched_pixels = compare_pair_of_images(&image_pair, pixel_tolerance);
-            // TODO: create model to hold image location and mismatched pixels, maybe ImageHolders and mismatched
-            //          then need to create image with mismatched pixels business
-            //  do first line of todo first, rename current branch - can then do the image mismatch creation
-            if !mismatched_pixels.is_empty() {
-                create_mismatch_images_set.spawn_blocking(move || {
-                    create_mismatched_image(
-                        // TODO: pass in required values from config
-                        image_pair,
-                        mismatched_pixels,
-                    );
-                });
-            }
-        }
+    run_results.sort();
+    write_run_outputs(&config, &run_results)?;
 
-        Ok(())
-    })
+    Ok(run_results)
 }
 
-fn create_mismatched_image(
-    image_pair: (ImageHolder, ImageHolder),
-    mismatched_pixels: Vec<PixelCoord>,
-) -> ComparisonResult {
-    // TODO: create comparison image here
-    ComparisonResult::new(image_pair, mismatched_pixels, None)
+/// Starts comparing every image pair found via the config on a background thread, returning a
+/// receiver of per-pair `ComparisonEvent`s as they happen.
+///
+/// The receiver closes once every pair has been handled. Dropping it stops the run early, any
+/// pair already being compared is allowed to finish but no further events are produced.
+///
+/// From synchronous code use `Receiver::blocking_recv`, from async code `Receiver::recv`.
+pub fn stream(config: impl Into<CompareConfig>) -> Receiver<ComparisonEvent> {
+    let config = config.into();
+    let (sender, receiver) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+
+    thread::spawn(move || {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(send_comparison_events(config, sender));
+    });
+
+    receiver
 }
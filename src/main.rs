@@ -1,24 +1,120 @@
 use std::process;
 
 use clap::Parser;
+use log::LevelFilter;
 
-use image_visual_comparator::{config::AppConfig, logger::Logger, run};
+use image_visual_comparator::{
+    approve, compare_image_files,
+    config::{AppConfig, Command, CompareConfig},
+    list_snapshots,
+    logger::Logger,
+    merge_reports, plan, rollback, run, serve, watch, IVCError, PlanFormat, RunPlan,
+};
+
+const SUCCESS_EXIT_CODE: i32 = 0;
+const FAILURE_EXIT_CODE: i32 = 1;
+// clap already exits with 2 for invalid arguments, so mismatches use their own code
+const MISMATCH_EXIT_CODE: i32 = 3;
 
 fn main() {
-    let config = AppConfig::parse();
+    let mut app_config = AppConfig::parse();
+    let command = app_config.take_command();
+    let is_watching = app_config.is_watching();
+    let dry_run = app_config.get_dry_run();
+    let config = match CompareConfig::try_from(app_config) {
+        Ok(config) => config,
+        Err(error) => {
+            Logger::setup(LevelFilter::Info);
+            exit_with_failure(error);
+        }
+    };
+
+    // a json plan is written to stdout, so is kept free of log lines other than errors
+    match dry_run {
+        Some(PlanFormat::Json) => Logger::setup(config.get_log_level().min(LevelFilter::Error)),
+        _ => Logger::setup(config.get_log_level()),
+    }
 
-    Logger::setup(config.get_log_level());
+    // Ok holds whether any images were mismatched
+    let result: Result<bool, IVCError> = match command {
+        None if dry_run.is_some() => plan(config).map(|run_plan| {
+            output_run_plan(&run_plan, dry_run.unwrap_or_default());
+            false
+        }),
+        None if is_watching => watch(config).map(|_| false),
+        None => run(config).map(|run_results| run_results.get_mismatched_pairs().count() > 0),
+        Some(Command::Compare(options)) => compare_image_files(
+            &options.original,
+            &options.latest,
+            options.out.as_deref(),
+            &config.get_compare_options(),
+        )
+        .map(|comparison_result| comparison_result.is_mismatched()),
+        Some(Command::Approve(options)) => approve(config, &options).map(|_| false),
+        Some(Command::Rollback(options)) if options.list => {
+            list_snapshots(config).map(|snapshots| {
+                for snapshot in snapshots.iter() {
+                    Logger::info(format!(
+                        "snapshot: '{}', baselines: '{}'",
+                        snapshot.id,
+                        snapshot.entries.len()
+                    ));
+                }
+                false
+            })
+        }
+        Some(Command::Rollback(options)) => {
+            rollback(config, options.snapshot.as_deref()).map(|_| false)
+        }
+        Some(Command::Serve(options)) => serve(config, options.port).map(|_| false),
+        Some(Command::MergeReports(options)) => merge_reports(config, &options.results)
+            .map(|run_results| run_results.get_mismatched_pairs().count() > 0),
+    };
 
-    match run(config) {
-        Ok(_) => {
-            // TODO: do something with this, maybe log some more values ?
+    match result {
+        Ok(false) => {
             Logger::info("Completed".to_string());
-            process::exit(0);
+            process::exit(SUCCESS_EXIT_CODE);
         }
-        Err(_error) => {
-            // TODO: Log error to string here
-            Logger::info("Failed".to_string());
-            process::exit(1);
+        Ok(true) => {
+            Logger::info("Completed with mismatched images".to_string());
+            process::exit(MISMATCH_EXIT_CODE);
         }
+        Err(error) => exit_with_failure(error),
     }
 }
+
+fn output_run_plan(run_plan: &RunPlan, format: PlanFormat) {
+    match format {
+        PlanFormat::Json => match serde_json::to_string_pretty(run_plan) {
+            Ok(json) => println!("{json}"),
+            Err(error) => Logger::error(format!("Could not write plan as json: '{error}'")),
+        },
+        PlanFormat::Text => {
+            for pair in run_plan.pairs.iter() {
+                Logger::info(format!(
+                    "pair: '{}', original: '{}'",
+                    pair.latest, pair.original
+                ));
+            }
+            for location in run_plan.new_images.iter() {
+                Logger::info(format!("new image: '{location}'"));
+            }
+            for location in run_plan.missing_images.iter() {
+                Logger::info(format!("missing image: '{location}'"));
+            }
+            for location in run_plan.filtered_files.iter() {
+                Logger::info(format!("filtered file: '{location}'"));
+            }
+            if let Some(failure) = &run_plan.failure {
+                Logger::warn(format!("The run would fail: {failure}"));
+            }
+        }
+    }
+}
+
+fn exit_with_failure(error: IVCError) -> ! {
+    Logger::error(error.to_string());
+    Logger::info("Failed".to_string());
+    process::exit(FAILURE_EXIT_CODE);
+}
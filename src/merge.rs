@@ -0,0 +1,160 @@
+use std::fs;
+
+use crate::{
+    config::CompareConfig,
+    errors::{handling::create_no_shard_results_error, ivc::IVCError},
+    logger::Logger,
+    models::RunResults,
+    utils::file_system::results::{read_run_results, write_run_outputs},
+};
+
+const SHARD_RESULTS_PREFIX: &str = "results.shard-";
+const SHARD_RESULTS_EXTENSION: &str = ".json";
+
+/// Combines the results files of the shards of a run into the results file and html report of the
+/// run as a whole.
+///
+/// When no results files are passed, every shard's results file in the mismatched directory is
+/// merged.
+pub fn merge_reports(
+    config: impl Into<CompareConfig>,
+    results_locations: &[String],
+) -> Result<RunResults, IVCError> {
+    let config = config.into().without_shard();
+
+    let results_locations = match results_locations.is_empty() {
+        true => find_shard_results_locations(&config.get_mismatched_images_dir()),
+        false => results_locations.to_vec(),
+    };
+    if results_locations.is_empty() {
+        return Err(create_no_shard_results_error(
+            &config.get_mismatched_images_dir(),
+        ));
+    }
+
+    let mut run_results = RunResults::default();
+    for results_location in results_locations.iter() {
+        run_results.merge(read_run_results(results_location)?);
+        Logger::debug(format!("merged shard results: '{results_location}'"));
+    }
+
+    write_run_outputs(&config, &run_results)?;
+
+    Logger::info(format!(
+        "Merged shard results - number of results files: '{}', number of pairs: '{}'",
+        results_locations.len(),
+        run_results.pairs.len()
+    ));
+
+    Ok(run_results)
+}
+
+fn find_shard_results_locations(dir: &str) -> Vec<String> {
+    let mut locations: Vec<String> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                file_name.starts_with(SHARD_RESULTS_PREFIX)
+                    && file_name.ends_with(SHARD_RESULTS_EXTENSION)
+            })
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    locations.sort();
+
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    mod merge_reports {
+        use std::path::Path;
+
+        use crate::{
+            config::CompareConfig,
+            merge::merge_reports,
+            models::{PairStatus, Shard},
+            run,
+            test_utils::{
+                files::create_temp_dir_handler,
+                image::{change_pixel_on_img, create_dynamic_image},
+            },
+            utils::file_system::results::read_run_results,
+        };
+
+        #[test]
+        fn combines_every_shard_into_the_results_of_the_whole_run() {
+            let temp_dir_handler = create_temp_dir_handler();
+            for dir in ["original", "latest"] {
+                temp_dir_handler.create_dir_in_temp_dir(dir);
+            }
+            for index in 0..6 {
+                let mut latest_image = create_dynamic_image(5, 5);
+                if index % 2 == 0 {
+                    change_pixel_on_img(&mut latest_image, 1, 1);
+                }
+                create_dynamic_image(5, 5)
+                    .save(
+                        temp_dir_handler
+                            .get_location_of_file_name(&format!("original/{index}.png")),
+                    )
+                    .unwrap();
+                latest_image
+                    .save(
+                        temp_dir_handler.get_location_of_file_name(&format!("latest/{index}.png")),
+                    )
+                    .unwrap();
+            }
+
+            let mut shard_pair_count = 0;
+            for index in 1..=3 {
+                let config = CompareConfig::builder()
+                    .directory(temp_dir_handler.get_temp_dir_path())
+                    .shard(Shard::new(index, 3).unwrap())
+                    .build();
+                shard_pair_count += run(config.clone()).unwrap().pairs.len();
+                assert!(Path::new(&config.get_results_file_location()).is_file());
+            }
+
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .build();
+            let merged = merge_reports(config.clone(), &[]).unwrap();
+
+            assert_eq!(6, shard_pair_count);
+            assert_eq!(6, merged.pairs.len());
+            assert_eq!(3, merged.get_mismatched_pairs().count());
+            assert_eq!(
+                PairStatus::Mismatched,
+                merged.pairs[0].status,
+                "pairs should be sorted, with '0.png' first"
+            );
+            assert_eq!(
+                merged,
+                read_run_results(&config.get_results_file_location()).unwrap()
+            );
+            assert!(Path::new(&config.get_report_file_location()).is_file());
+        }
+
+        #[test]
+        fn returns_error_when_there_are_no_shard_results() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .build();
+
+            let result = merge_reports(config.clone(), &[]);
+
+            assert_eq!(
+                format!(
+                    "Could not find any shard results to merge in directory: '{}'.",
+                    config.get_mismatched_images_dir()
+                ),
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+}
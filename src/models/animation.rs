@@ -0,0 +1,107 @@
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use super::PixelCoord;
+
+/// A single frame of an animated image, composed onto the full canvas of the image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFrame {
+    pub image: DynamicImage,
+    pub delay_ms: u32,
+}
+
+impl AnimationFrame {
+    pub fn new(image: DynamicImage, delay_ms: u32) -> Self {
+        AnimationFrame { image, delay_ms }
+    }
+}
+
+/// The comparison of a single frame of an animated pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameResult {
+    pub index: usize,
+    pub original_delay_ms: u32,
+    pub latest_delay_ms: u32,
+    pub mismatched_pixel_count: usize,
+}
+
+/// The frame by frame comparison of a pair where either image is animated, a still image being
+/// treated as a single frame with no delay.
+///
+/// Only the frames found in both images are compared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnimationResult {
+    pub original_frame_count: usize,
+    pub latest_frame_count: usize,
+    pub frames: Vec<FrameResult>,
+}
+
+impl AnimationResult {
+    /// Whether the animations have the same number of frames, shown for the same delays, with no
+    /// mismatched pixels in any frame.
+    pub fn is_matching(&self) -> bool {
+        self.original_frame_count == self.latest_frame_count
+            && self.frames.iter().all(|frame| {
+                frame.original_delay_ms == frame.latest_delay_ms
+                    && frame.mismatched_pixel_count == 0
+            })
+    }
+}
+
+/// The comparison of an animated pair, along with the pixels mismatched in each compared frame
+/// for creating the animated mismatch image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationComparison {
+    pub result: AnimationResult,
+    pub frame_mismatched_pixels: Vec<Vec<PixelCoord>>,
+}
+
+impl AnimationComparison {
+    /// The pixels mismatched in any compared frame, each only once.
+    pub fn get_mismatched_pixels(&self) -> Vec<PixelCoord> {
+        let mut mismatched_pixels: Vec<PixelCoord> = self
+            .frame_mismatched_pixels
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        mismatched_pixels.sort_by_key(|pixel_coord| (pixel_coord.y, pixel_coord.x));
+        mismatched_pixels.dedup();
+
+        mismatched_pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod is_matching {
+        use crate::models::{AnimationResult, FrameResult};
+
+        fn create_animation_result(
+            latest_frame_count: usize,
+            latest_delay_ms: u32,
+            mismatched_pixel_count: usize,
+        ) -> AnimationResult {
+            AnimationResult {
+                original_frame_count: 2,
+                latest_frame_count,
+                frames: (0..2)
+                    .map(|index| FrameResult {
+                        index,
+                        original_delay_ms: 100,
+                        latest_delay_ms,
+                        mismatched_pixel_count,
+                    })
+                    .collect(),
+            }
+        }
+
+        #[test]
+        fn only_when_frame_counts_delays_and_pixels_match() {
+            assert!(create_animation_result(2, 100, 0).is_matching());
+            assert!(!create_animation_result(3, 100, 0).is_matching());
+            assert!(!create_animation_result(2, 50, 0).is_matching());
+            assert!(!create_animation_result(2, 100, 1).is_matching());
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// A change made to the original images when approving latest images as the new baselines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaselineChange {
+    /// An existing original was replaced by its mismatched latest image.
+    Updated { latest: String, original: String },
+    /// A latest image with no original was added as a new original.
+    Added { latest: String, original: String },
+    /// An original with no latest image was removed.
+    Removed { original: String },
+}
+
+impl BaselineChange {
+    pub fn get_original(&self) -> &str {
+        match self {
+            BaselineChange::Updated { original, .. }
+            | BaselineChange::Added { original, .. }
+            | BaselineChange::Removed { original } => original,
+        }
+    }
+}
+
+impl fmt::Display for BaselineChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BaselineChange::Updated { latest, original } => {
+                write!(f, "updated '{original}' from '{latest}'")
+            }
+            BaselineChange::Added { latest, original } => {
+                write!(f, "added '{original}' from '{latest}'")
+            }
+            BaselineChange::Removed { original } => write!(f, "removed '{original}'"),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How the difference between two pixels is measured before being checked against the tolerance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ColourMetric {
+    /// The squared distance between the lab colours of the pixels.
+    #[default]
+    LabSquared,
+    /// The distance between the lab colours of the pixels, also known as CIE76 delta E.
+    Lab,
+    /// The largest difference between any one of the pixels' red, green, blue or alpha channels.
+    Rgba,
+}
@@ -0,0 +1,22 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The precision pixels are compared at, which only matters for images with more than 8 bits per
+/// channel, such as 16-bit pngs and 32-bit float images.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ColourPrecision {
+    /// Every pixel is converted to 8 bits per channel before being compared, so differences
+    /// smaller than 1/255 are not seen.
+    #[default]
+    EightBit,
+    /// Images with more than 8 bits per channel are compared at their own precision.
+    ///
+    /// Differences are still measured on the 8-bit scale, so a tolerance means the same as for
+    /// 8-bit images, but fractions of an 8-bit step are kept. 16-bit channels are scaled to
+    /// 0 - 1, float channels are used as they are. The lab metrics clamp channels to 0 - 1, as lab
+    /// colours are only defined for that range, while the rgba metric also compares float
+    /// channels above 1.
+    Native,
+}
@@ -0,0 +1,37 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How the icc colour profiles embedded in images are handled, images without one being treated
+/// as sRGB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum ProfileHandling {
+    /// Profiles are neither converted nor recorded, pixels being compared as they are stored.
+    Ignore,
+    /// Pixels are compared as they are stored, with a warning logged for each pair whose
+    /// profiles differ.
+    #[default]
+    Warn,
+    /// Images with a profile other than sRGB are converted to sRGB before being compared, at 8
+    /// bits per channel.
+    Convert,
+}
+
+/// An icc colour profile embedded in an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourProfile {
+    /// The profile's description, such as `Display P3`.
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// The colour profiles of a pair where either image embeds one, `None` meaning the image has no
+/// profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileResult {
+    pub original: Option<String>,
+    pub latest: Option<String>,
+    /// Whether either image was converted to sRGB before being compared.
+    pub converted: bool,
+}
@@ -0,0 +1,131 @@
+use crate::config::DEFAULT_TOLERANCE;
+
+use super::{
+    ColourMetric, ColourPrecision, DiffOptions, Mask, PixelCoord, ProfileHandling, SvgOptions,
+};
+
+/// Options used by `compare_images` when comparing two in-memory images.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareOptions {
+    tolerance: f32,
+    metric: ColourMetric,
+    precision: ColourPrecision,
+    profiles: ProfileHandling,
+    threshold: f32,
+    masks: Vec<Mask>,
+    svg: SvgOptions,
+    diff: DiffOptions,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOLERANCE as f32)
+    }
+}
+
+impl CompareOptions {
+    /// The tolerance is the difference between two pixels, as measured by the metric, that is
+    /// allowed before they are counted as mismatched, 0 requiring an exact match. The metric
+    /// defaults to the squared distance between the pixels' lab colours.
+    pub fn new(tolerance: f32) -> Self {
+        CompareOptions {
+            tolerance,
+            metric: ColourMetric::default(),
+            precision: ColourPrecision::default(),
+            profiles: ProfileHandling::default(),
+            threshold: 0_f32,
+            masks: Vec::new(),
+            svg: SvgOptions::default(),
+            diff: DiffOptions::default(),
+        }
+    }
+
+    pub fn with_metric(mut self, metric: ColourMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// The precision pixels are compared at, defaults to converting every pixel to 8 bits per
+    /// channel.
+    pub fn with_precision(mut self, precision: ColourPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// How the icc colour profiles embedded in the images are handled, defaults to warning when
+    /// they differ.
+    pub fn with_profiles(mut self, profiles: ProfileHandling) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// The percentage of pixels (0 - 100) that may be mismatched with the images still being
+    /// treated as a match, in which case no mismatched pixels are reported.
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Areas of the images whose pixels are never compared.
+    pub fn with_masks(mut self, masks: impl Into<Vec<Mask>>) -> Self {
+        self.masks = masks.into();
+        self
+    }
+
+    /// How svg images are rasterised when the images are read from their files, before being
+    /// compared.
+    pub fn with_svg(mut self, svg: SvgOptions) -> Self {
+        self.svg = svg;
+        self
+    }
+
+    /// How the diff image of mismatched images is created and written.
+    pub fn with_diff(mut self, diff: DiffOptions) -> Self {
+        self.diff = diff;
+        self
+    }
+
+    pub fn get_tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    pub fn get_metric(&self) -> ColourMetric {
+        self.metric
+    }
+
+    pub fn get_precision(&self) -> ColourPrecision {
+        self.precision
+    }
+
+    pub fn get_profiles(&self) -> ProfileHandling {
+        self.profiles
+    }
+
+    pub fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub fn get_masks(&self) -> &[Mask] {
+        &self.masks
+    }
+
+    pub fn get_svg(&self) -> &SvgOptions {
+        &self.svg
+    }
+
+    pub fn get_diff(&self) -> &DiffOptions {
+        &self.diff
+    }
+
+    pub fn is_masked(&self, pixel_coord: &PixelCoord) -> bool {
+        self.masks.iter().any(|mask| mask.contains(pixel_coord))
+    }
+
+    pub fn is_within_threshold(&self, mismatched_pixel_count: usize, pixel_count: u64) -> bool {
+        if pixel_count == 0 {
+            return true;
+        }
+
+        (mismatched_pixel_count as f64 / pixel_count as f64) * 100_f64 <= self.threshold as f64
+    }
+}
@@ -0,0 +1,51 @@
+use crate::errors::ivc::IVCError;
+
+use super::{AnimationResult, ProfileResult, RegionCrop};
+
+/// Progress of a single original/latest pair as it moves through a comparison run.
+///
+/// Events for different pairs are interleaved, so each carries the locations of its pair.
+///
+/// New events, and new fields on existing events, may be added without a breaking release, so
+/// matches need a wildcard arm and patterns a `..`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ComparisonEvent {
+    /// The pair has been queued and its images are being read from the file system.
+    #[non_exhaustive]
+    Started { original: String, latest: String },
+    /// The pair has been compared, with the number and percentage of pixels that differ past the
+    /// tolerance, how many regions of touching pixels they form, the frame by frame comparison
+    /// when either image is animated, and the colour profiles when either image embeds one.
+    #[non_exhaustive]
+    Compared {
+        original: String,
+        latest: String,
+        mismatched_pixel_count: usize,
+        mismatch_percentage: f32,
+        region_count: usize,
+        animation: Option<AnimationResult>,
+        profiles: Option<ProfileResult>,
+    },
+    /// A mismatch image for the pair has been written to `location`, along with its blink gif
+    /// to `blink` and the zoomed crops of its regions when set.
+    #[non_exhaustive]
+    DiffWritten {
+        original: String,
+        latest: String,
+        location: String,
+        blink: Option<String>,
+        crops: Vec<RegionCrop>,
+    },
+    /// Not every image could be paired, sent before the run fails with an unpaired or image count
+    /// error.
+    #[non_exhaustive]
+    Unpaired {
+        /// Latest images with no matching original.
+        new_images: Vec<String>,
+        /// Original images with no matching latest.
+        missing_images: Vec<String>,
+    },
+    /// The run, or a single pair within it, could not be completed.
+    Failed(IVCError),
+}
@@ -0,0 +1,21 @@
+use super::PixelCoord;
+
+/// The outcome of comparing an original image against a latest image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComparisonOutcome {
+    /// Every pixel is within the tolerance.
+    Matched,
+    /// The co-ordinates of every pixel that differs past the tolerance.
+    Mismatched { mismatched_pixels: Vec<PixelCoord> },
+    /// The images could not be compared as their `(width, height)` differ.
+    DimensionMismatch {
+        original: (u32, u32),
+        latest: (u32, u32),
+    },
+}
+
+impl ComparisonOutcome {
+    pub fn is_match(&self) -> bool {
+        matches!(self, ComparisonOutcome::Matched)
+    }
+}
@@ -1,12 +1,21 @@
-use super::{ImageHolder, PixelCoord};
+use crate::utils::image::get_mismatch_percentage;
 
-#[derive(Debug, PartialEq)]
+use super::{AnimationResult, ImageHolder, MismatchRegion, PixelCoord, ProfileResult, RegionCrop};
+
+/// An original/latest pair of images, the pixels that differ between them and, when they do
+/// differ, the mismatch image that was created.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ComparisonResult {
     original_image: ImageHolder,
     latest_image: ImageHolder,
     // TODO: make non optional after creating image
     comparison_image: Option<ImageHolder>,
     mismatched_pixels: Vec<PixelCoord>,
+    regions: Vec<MismatchRegion>,
+    animation: Option<AnimationResult>,
+    profiles: Option<ProfileResult>,
+    blink_location: Option<String>,
+    crops: Vec<RegionCrop>,
 }
 
 impl ComparisonResult {
@@ -19,7 +28,113 @@ impl ComparisonResult {
             original_image: original_latest_image_pair.0,
             latest_image: original_latest_image_pair.1,
             mismatched_pixels,
+            regions: Vec::new(),
             comparison_image,
+            animation: None,
+            profiles: None,
+            blink_location: None,
+            crops: Vec::new(),
         }
     }
+
+    /// Sets the regions of touching mismatched pixels, found once so that the mismatch image,
+    /// blink gif, crops and results can share them.
+    pub fn with_regions(mut self, regions: Vec<MismatchRegion>) -> Self {
+        self.regions = regions;
+        self
+    }
+
+    /// Sets the frame by frame comparison, for pairs where either image is animated.
+    pub fn with_animation(mut self, animation: Option<AnimationResult>) -> Self {
+        self.animation = animation;
+        self
+    }
+
+    /// Sets the colour profiles of the pair, when either image embeds one.
+    pub fn with_profiles(mut self, profiles: Option<ProfileResult>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Sets where the blink gif of the pair was written, when one was.
+    pub fn with_blink_location(mut self, blink_location: Option<String>) -> Self {
+        self.blink_location = blink_location;
+        self
+    }
+
+    /// Sets the zoomed crops written for the regions of mismatched pixels.
+    pub fn with_crops(mut self, crops: Vec<RegionCrop>) -> Self {
+        self.crops = crops;
+        self
+    }
+
+    pub fn get_original_image(&self) -> &ImageHolder {
+        &self.original_image
+    }
+
+    pub fn get_latest_image(&self) -> &ImageHolder {
+        &self.latest_image
+    }
+
+    pub fn get_comparison_image(&self) -> Option<&ImageHolder> {
+        self.comparison_image.as_ref()
+    }
+
+    pub fn get_mismatched_pixels(&self) -> &[PixelCoord] {
+        &self.mismatched_pixels
+    }
+
+    pub fn get_regions(&self) -> &[MismatchRegion] {
+        &self.regions
+    }
+
+    pub fn get_animation(&self) -> Option<&AnimationResult> {
+        self.animation.as_ref()
+    }
+
+    pub fn get_profiles(&self) -> Option<&ProfileResult> {
+        self.profiles.as_ref()
+    }
+
+    pub fn get_blink_location(&self) -> Option<&str> {
+        self.blink_location.as_deref()
+    }
+
+    pub fn get_crops(&self) -> &[RegionCrop] {
+        &self.crops
+    }
+
+    /// Whether any pixels are mismatched or, for animated pairs, their frame counts or delays
+    /// differ.
+    pub fn is_mismatched(&self) -> bool {
+        !self.mismatched_pixels.is_empty()
+            || self
+                .animation
+                .as_ref()
+                .is_some_and(|animation| !animation.is_matching())
+    }
+
+    /// The number of pixels in each image of the pair.
+    pub fn get_pixel_count(&self) -> u64 {
+        self.latest_image.image.width() as u64 * self.latest_image.image.height() as u64
+    }
+
+    /// The percentage (0 - 100) of pixels that are mismatched.
+    pub fn get_mismatch_percentage(&self) -> f32 {
+        get_mismatch_percentage(self.mismatched_pixels.len(), self.get_pixel_count())
+    }
+
+    pub fn get_original_location(&self) -> &str {
+        &self.original_image.location
+    }
+
+    pub fn get_latest_location(&self) -> &str {
+        &self.latest_image.location
+    }
+
+    pub fn get_comparison_location(&self) -> Option<&str> {
+        self.comparison_image
+            .as_ref()
+            .map(|holder| holder.location.as_str())
+    }
 }
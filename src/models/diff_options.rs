@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The format diff images are written in, animated diffs always being written as gif.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum DiffFormat {
+    #[default]
+    Png,
+    /// Lossless webp.
+    Webp,
+    /// Written without an alpha channel, at 8 bits per channel.
+    Jpeg,
+}
+
+impl DiffFormat {
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            DiffFormat::Png => "png",
+            DiffFormat::Webp => "webp",
+            DiffFormat::Jpeg => "jpg",
+        }
+    }
+}
+
+/// How the images of a mismatched pair are laid out in its diff image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum DiffLayout {
+    /// The latest image with its mismatched pixels highlighted.
+    #[default]
+    DiffOnly,
+    /// The original image, the highlighted latest image and the latest image, side by side.
+    Triptych,
+    /// The original image's brightness in the red channel and the latest image's in the green
+    /// channel, so that unchanged areas are yellow and changes show as red or green.
+    Overlay,
+    /// Each differing pixel coloured by how much it differs, from blue to red, over the latest
+    /// image in greyscale, with a legend of the colour ramp beneath.
+    Heatmap,
+}
+
+/// A colour, parsed from a hex code such as `#ff00ff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct HighlightColour(pub [u8; 3]);
+
+impl Default for HighlightColour {
+    fn default() -> Self {
+        HighlightColour([255, 0, 0])
+    }
+}
+
+impl FromStr for HighlightColour {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let hex = value.strip_prefix('#').unwrap_or(value);
+        let channel = |index: usize| {
+            hex.get(index * 2..index * 2 + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+
+        match (hex.len(), channel(0), channel(1), channel(2)) {
+            (6, Some(red), Some(green), Some(blue)) => Ok(HighlightColour([red, green, blue])),
+            _ => Err(format!("'{value}' is not a hex colour, such as '#ff0000'")),
+        }
+    }
+}
+
+impl TryFrom<String> for HighlightColour {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        HighlightColour::from_str(&value)
+    }
+}
+
+/// How the diff image of a mismatched pair is created and written.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiffOptions {
+    format: DiffFormat,
+    layout: DiffLayout,
+    highlight: HighlightColour,
+    fade: f32,
+    outlines: bool,
+    blink: bool,
+    crops: bool,
+}
+
+impl DiffOptions {
+    pub fn with_format(mut self, format: DiffFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: DiffLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// The colour mismatched pixels and region outlines are drawn in, defaults to red.
+    pub fn with_highlight(mut self, highlight: HighlightColour) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// The percentage (0 - 100) the pixels that are not mismatched are faded towards white, so
+    /// that the highlighted pixels stand out.
+    pub fn with_fade(mut self, fade: f32) -> Self {
+        self.fade = fade;
+        self
+    }
+
+    /// Whether an outline is drawn around each region of touching mismatched pixels.
+    pub fn with_outlines(mut self, outlines: bool) -> Self {
+        self.outlines = outlines;
+        self
+    }
+
+    /// Whether an animated gif alternating the original and latest image, with each region of
+    /// mismatched pixels outlined, is written alongside the diff image.
+    pub fn with_blink(mut self, blink: bool) -> Self {
+        self.blink = blink;
+        self
+    }
+
+    /// Whether zoomed crops of the original, latest and diff image are written for each region of
+    /// mismatched pixels, alongside the diff image.
+    pub fn with_crops(mut self, crops: bool) -> Self {
+        self.crops = crops;
+        self
+    }
+
+    pub fn get_format(&self) -> DiffFormat {
+        self.format
+    }
+
+    pub fn get_layout(&self) -> DiffLayout {
+        self.layout
+    }
+
+    pub fn get_highlight(&self) -> HighlightColour {
+        self.highlight
+    }
+
+    pub fn get_fade(&self) -> f32 {
+        self.fade
+    }
+
+    pub fn has_outlines(&self) -> bool {
+        self.outlines
+    }
+
+    pub fn has_blink(&self) -> bool {
+        self.blink
+    }
+
+    pub fn has_crops(&self) -> bool {
+        self.crops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod highlight_colour_from_str {
+        use std::str::FromStr;
+
+        use crate::models::HighlightColour;
+
+        #[test]
+        fn parses_hex_codes_with_or_without_hash() {
+            assert_eq!(
+                Ok(HighlightColour([255, 0, 170])),
+                HighlightColour::from_str("#ff00AA")
+            );
+            assert_eq!(
+                Ok(HighlightColour([0, 128, 255])),
+                HighlightColour::from_str("0080ff")
+            );
+        }
+
+        #[test]
+        fn returns_error_when_not_a_hex_code() {
+            for value in ["red", "#ff00", "#gg0000", "#ff0000ff"] {
+                assert!(HighlightColour::from_str(value).is_err());
+            }
+        }
+    }
+}
@@ -1,9 +1,19 @@
+use std::borrow::Cow;
+
 use image::DynamicImage;
 
-#[derive(Debug, PartialEq)]
+use super::{AnimationFrame, ColourProfile};
+
+/// An image along with the location it was read from, or written to.
+///
+/// For an animated image, `image` holds its first frame and `get_frames` every frame.
+/// `get_profile` is the icc colour profile embedded in the image, if any.
+#[derive(Debug, Clone, PartialEq)]
 pub struct ImageHolder {
     pub image: DynamicImage,
     pub location: String,
+    pub(crate) frames: Vec<AnimationFrame>,
+    pub(crate) profile: Option<ColourProfile>,
 }
 
 impl ImageHolder {
@@ -11,6 +21,34 @@ impl ImageHolder {
         ImageHolder {
             image,
             location: location.to_string(),
+            frames: Vec::new(),
+            profile: None,
+        }
+    }
+
+    pub fn with_frames(mut self, frames: Vec<AnimationFrame>) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: Option<ColourProfile>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn get_profile(&self) -> Option<&ColourProfile> {
+        self.profile.as_ref()
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    /// The frames of the image, a still image being a single frame with no delay.
+    pub fn get_frames(&self) -> Cow<'_, [AnimationFrame]> {
+        match self.frames.is_empty() {
+            true => Cow::Owned(vec![AnimationFrame::new(self.image.clone(), 0)]),
+            false => Cow::Borrowed(&self.frames),
         }
     }
 }
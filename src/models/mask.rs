@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use super::PixelCoord;
+
+/// A rectangle of pixels that is ignored when comparing, such as an area showing the current time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mask {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Mask {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Mask {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn contains(&self, pixel_coord: &PixelCoord) -> bool {
+        (self.x..self.x.saturating_add(self.width)).contains(&pixel_coord.x)
+            && (self.y..self.y.saturating_add(self.height)).contains(&pixel_coord.y)
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// The bounding box of a region of touching mismatched pixels, including diagonally touching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MismatchRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MismatchRegion {
+    pub fn get_area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+}
+
+/// The zoomed crop thumbnails written for a single region of a mismatched pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionCrop {
+    pub region: MismatchRegion,
+    pub original: String,
+    pub latest: String,
+    pub diff: String,
+}
+
+impl RegionCrop {
+    pub fn get_locations(&self) -> [&str; 3] {
+        [&self.original, &self.latest, &self.diff]
+    }
+}
@@ -1,7 +1,39 @@
+mod animation;
+mod baseline_change;
+mod colour_metric;
+mod colour_precision;
+mod colour_profile;
+mod compare_options;
+mod comparison_event;
+mod comparison_outcome;
 mod comparison_result;
+mod diff_options;
 mod image_holder;
+mod mask;
+mod mismatch_region;
 mod pixel_coord;
+mod run_plan;
+mod run_results;
+mod shard;
+mod snapshot_manifest;
+mod svg_options;
 
+pub use animation::{AnimationComparison, AnimationFrame, AnimationResult, FrameResult};
+pub use baseline_change::BaselineChange;
+pub use colour_metric::ColourMetric;
+pub use colour_precision::ColourPrecision;
+pub use colour_profile::{ColourProfile, ProfileHandling, ProfileResult};
+pub use compare_options::CompareOptions;
+pub use comparison_event::ComparisonEvent;
+pub use comparison_outcome::ComparisonOutcome;
 pub use comparison_result::ComparisonResult;
+pub use diff_options::{DiffFormat, DiffLayout, DiffOptions, HighlightColour};
 pub use image_holder::ImageHolder;
+pub use mask::Mask;
+pub use mismatch_region::{MismatchRegion, RegionCrop};
 pub use pixel_coord::PixelCoord;
+pub use run_plan::{PlanFormat, PlannedPair, RunPlan};
+pub use run_results::{PairResult, PairStatus, ReviewDecision, RunResults};
+pub use shard::Shard;
+pub use snapshot_manifest::{SnapshotEntry, SnapshotManifest};
+pub use svg_options::SvgOptions;
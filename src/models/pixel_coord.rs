@@ -1,4 +1,5 @@
-#[derive(Debug, PartialEq)]
+/// The co-ordinates of a pixel, with `(0, 0)` being the top left of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PixelCoord {
     pub x: u32,
     pub y: u32,
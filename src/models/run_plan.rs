@@ -0,0 +1,37 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How a dry run's plan is output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum PlanFormat {
+    /// A log line per pair, image and filtered file.
+    #[default]
+    Text,
+    /// The plan as json, with nothing else written to stdout.
+    Json,
+}
+
+/// An original/latest pair that a run would compare.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedPair {
+    pub original: String,
+    pub latest: String,
+}
+
+/// What a run would do with the images found via the config, worked out without decoding any of
+/// them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunPlan {
+    pub pairs: Vec<PlannedPair>,
+    /// Latest images with no matching original.
+    pub new_images: Vec<String>,
+    /// Original images with no matching latest.
+    pub missing_images: Vec<String>,
+    /// Files in the original or latest directories that would be skipped, as they do not have
+    /// one of the configured extensions, are excluded by a glob or belong to another shard.
+    pub filtered_files: Vec<String>,
+    /// Why the run would fail before comparing any pair, such as the images not being paired.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+
+use super::{AnimationResult, ComparisonEvent, ComparisonResult, ProfileResult, RegionCrop};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairStatus {
+    Matched,
+    Mismatched,
+}
+
+/// A reviewer's decision on a mismatched pair, made via the review server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecision {
+    /// The latest image was promoted to be the new original.
+    Approved,
+    /// The latest image was kept out of the originals, as the change is unwanted.
+    Rejected,
+}
+
+/// The result of comparing a single original/latest pair during a run.
+///
+/// New fields may be added without a breaking release, so pair results are read from a run's
+/// results rather than built.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct PairResult {
+    pub original: String,
+    pub latest: String,
+    pub status: PairStatus,
+    pub mismatched_pixel_count: usize,
+    /// The percentage (0 - 100) of pixels that are mismatched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mismatch_percentage: Option<f32>,
+    /// How many regions of touching mismatched pixels there are.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region_count: Option<usize>,
+    pub diff: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewDecision>,
+    /// The frame by frame comparison, when either image of the pair is animated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationResult>,
+    /// The colour profiles of the pair, when either image embeds one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<ProfileResult>,
+    /// The animated gif alternating the original and latest image, when one was written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blink: Option<String>,
+    /// The zoomed crops of the largest regions of mismatched pixels, when they were written.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub crops: Vec<RegionCrop>,
+}
+
+impl PairResult {
+    /// The locations of the images written for the pair, such as its diff, blink gif and region
+    /// crops.
+    pub fn get_written_locations(&self) -> impl Iterator<Item = &str> {
+        self.diff
+            .iter()
+            .chain(self.blink.iter())
+            .map(String::as_str)
+            .chain(self.crops.iter().flat_map(RegionCrop::get_locations))
+    }
+}
+
+impl From<&ComparisonResult> for PairResult {
+    fn from(comparison_result: &ComparisonResult) -> Self {
+        let mismatched_pixel_count = comparison_result.get_mismatched_pixels().len();
+
+        PairResult {
+            original: comparison_result.get_original_location().to_owned(),
+            latest: comparison_result.get_latest_location().to_owned(),
+            status: match comparison_result.is_mismatched() {
+                false => PairStatus::Matched,
+                true => PairStatus::Mismatched,
+            },
+            mismatched_pixel_count,
+            mismatch_percentage: Some(comparison_result.get_mismatch_percentage()),
+            region_count: Some(comparison_result.get_regions().len()),
+            diff: comparison_result
+                .get_comparison_location()
+                .map(|location| location.to_owned()),
+            review: None,
+            animation: comparison_result.get_animation().cloned(),
+            profiles: comparison_result.get_profiles().cloned(),
+            blink: comparison_result
+                .get_blink_location()
+                .map(|location| location.to_owned()),
+            crops: comparison_result.get_crops().to_vec(),
+        }
+    }
+}
+
+/// The results of a comparison run, written to the results file in the mismatched directory so
+/// that later commands, such as `approve`, can act on them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunResults {
+    pub pairs: Vec<PairResult>,
+    /// Latest images with no matching original.
+    pub new_images: Vec<String>,
+    /// Original images with no matching latest.
+    pub missing_images: Vec<String>,
+}
+
+impl RunResults {
+    pub fn record_event(&mut self, event: &ComparisonEvent) {
+        match event {
+            ComparisonEvent::Compared {
+                original,
+                latest,
+                mismatched_pixel_count,
+                mismatch_percentage,
+                region_count,
+                animation,
+                profiles,
+            } => self.pairs.push(PairResult {
+                original: original.to_owned(),
+                latest: latest.to_owned(),
+                status: match *mismatched_pixel_count == 0
+                    && animation.as_ref().is_none_or(AnimationResult::is_matching)
+                {
+                    true => PairStatus::Matched,
+                    false => PairStatus::Mismatched,
+                },
+                mismatched_pixel_count: *mismatched_pixel_count,
+                mismatch_percentage: Some(*mismatch_percentage),
+                region_count: Some(*region_count),
+                diff: None,
+                review: None,
+                animation: animation.to_owned(),
+                profiles: profiles.to_owned(),
+                blink: None,
+                crops: Vec::new(),
+            }),
+            ComparisonEvent::DiffWritten {
+                latest,
+                location,
+                blink,
+                crops,
+                ..
+            } => {
+                if let Some(pair) = self.pairs.iter_mut().find(|pair| &pair.latest == latest) {
+                    pair.diff = Some(location.to_owned());
+                    pair.blink = blink.to_owned();
+                    pair.crops = crops.to_owned();
+                }
+            }
+            ComparisonEvent::Unpaired {
+                new_images,
+                missing_images,
+            } => {
+                self.new_images = new_images.to_owned();
+                self.missing_images = missing_images.to_owned();
+            }
+            ComparisonEvent::Started { .. } | ComparisonEvent::Failed(_) => {}
+        }
+    }
+
+    /// Orders the pairs by their latest location, as events for pairs arrive in any order.
+    pub fn sort(&mut self) {
+        self.pairs.sort_by(|one, two| one.latest.cmp(&two.latest));
+    }
+
+    /// Adds the results of another run, such as another shard of the same run, keeping only the
+    /// first result for any pair or image that appears in both.
+    pub fn merge(&mut self, other: RunResults) {
+        self.pairs.extend(other.pairs);
+        self.new_images.extend(other.new_images);
+        self.missing_images.extend(other.missing_images);
+
+        self.sort();
+        self.pairs.dedup_by(|one, two| one.latest == two.latest);
+        for images in [&mut self.new_images, &mut self.missing_images] {
+            images.sort();
+            images.dedup();
+        }
+    }
+
+    pub fn get_mismatched_pairs(&self) -> impl Iterator<Item = &PairResult> {
+        self.pairs
+            .iter()
+            .filter(|pair| pair.status == PairStatus::Mismatched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod record_event {
+        use crate::models::{ComparisonEvent, PairStatus, RunResults};
+
+        #[test]
+        fn builds_pair_results_from_compared_and_diff_written_events() {
+            let mut run_results = RunResults::default();
+
+            for event in [
+                ComparisonEvent::Started {
+                    original: "original/b.png".to_string(),
+                    latest: "latest/b.png".to_string(),
+                },
+                ComparisonEvent::Compared {
+                    original: "original/b.png".to_string(),
+                    latest: "latest/b.png".to_string(),
+                    mismatched_pixel_count: 3,
+                    mismatch_percentage: 0.75,
+                    region_count: 2,
+                    animation: None,
+                    profiles: None,
+                },
+                ComparisonEvent::Compared {
+                    original: "original/a.png".to_string(),
+                    latest: "latest/a.png".to_string(),
+                    mismatched_pixel_count: 0,
+                    mismatch_percentage: 0_f32,
+                    region_count: 0,
+                    animation: None,
+                    profiles: None,
+                },
+                ComparisonEvent::DiffWritten {
+                    original: "original/b.png".to_string(),
+                    latest: "latest/b.png".to_string(),
+                    location: "mismatched/b.png".to_string(),
+                    blink: Some("mismatched/b.blink.gif".to_string()),
+                    crops: Vec::new(),
+                },
+            ] {
+                run_results.record_event(&event);
+            }
+            run_results.sort();
+
+            assert_eq!(2, run_results.pairs.len());
+            assert_eq!(PairStatus::Matched, run_results.pairs[0].status);
+            assert_eq!(None, run_results.pairs[0].diff);
+            assert_eq!(PairStatus::Mismatched, run_results.pairs[1].status);
+            assert_eq!(3, run_results.pairs[1].mismatched_pixel_count);
+            assert_eq!(
+                Some("mismatched/b.png".to_string()),
+                run_results.pairs[1].diff
+            );
+            assert_eq!(Some(2), run_results.pairs[1].region_count);
+            assert_eq!(
+                Some("mismatched/b.blink.gif".to_string()),
+                run_results.pairs[1].blink
+            );
+            assert_eq!(1, run_results.get_mismatched_pairs().count());
+        }
+
+        #[test]
+        fn records_new_and_missing_images_from_unpaired_event() {
+            let mut run_results = RunResults::default();
+
+            run_results.record_event(&ComparisonEvent::Unpaired {
+                new_images: vec!["latest/new.png".to_string()],
+                missing_images: vec!["original/gone.png".to_string()],
+            });
+
+            assert_eq!(vec!["latest/new.png".to_string()], run_results.new_images);
+            assert_eq!(
+                vec!["original/gone.png".to_string()],
+                run_results.missing_images
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::{fmt, str::FromStr};
+
+/// One of `count` shards a run is split into, so that each can be compared on its own worker.
+///
+/// The `index` is 1-based, so the shards of a run split in two are `1/2` and `2/2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    index: u32,
+    count: u32,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl Shard {
+    /// Returns `None` unless `1 <= index <= count`.
+    pub fn new(index: u32, count: u32) -> Option<Self> {
+        (index >= 1 && index <= count).then_some(Shard { index, count })
+    }
+
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+
+    /// Whether the key, such as an image's path relative to its directory, is assigned to this
+    /// shard.
+    ///
+    /// Keys are hashed with FNV-1a, rather than the standard library's hasher, so that every
+    /// worker assigns the same key to the same shard regardless of how it was built.
+    pub fn contains(&self, key: &str) -> bool {
+        let hash = key.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        });
+
+        hash % self.count as u64 == (self.index - 1) as u64
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_shard =
+            || format!("'{value}' is not a shard such as '1/4', from 1 to the count");
+
+        let (index, count) = value.split_once('/').ok_or_else(invalid_shard)?;
+        let index = index.trim().parse().map_err(|_| invalid_shard())?;
+        let count = count.trim().parse().map_err(|_| invalid_shard())?;
+
+        Shard::new(index, count).ok_or_else(invalid_shard)
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_str {
+        use std::str::FromStr;
+
+        use crate::models::Shard;
+
+        #[test]
+        fn returns_shard_when_index_is_within_count() {
+            assert_eq!(Shard::new(2, 4), Shard::from_str("2/4").ok());
+            assert_eq!("2/4", Shard::from_str("2/4").unwrap().to_string());
+        }
+
+        #[test]
+        fn returns_error_when_not_a_valid_shard() {
+            for value in ["0/4", "5/4", "1/0", "1", "a/b"] {
+                assert!(
+                    Shard::from_str(value).is_err(),
+                    "'{value}' should be invalid"
+                );
+            }
+        }
+    }
+
+    mod contains {
+        use crate::models::Shard;
+
+        #[test]
+        fn assigns_every_key_to_exactly_one_shard() {
+            let shards: Vec<Shard> = (1..=3).map(|index| Shard::new(index, 3).unwrap()).collect();
+
+            for key_index in 0..50 {
+                let key = format!("checkout/step_{key_index}.png");
+                let assigned_count = shards.iter().filter(|shard| shard.contains(&key)).count();
+
+                assert_eq!(1, assigned_count);
+            }
+            assert!((0..50)
+                .any(|key_index| shards[1].contains(&format!("checkout/step_{key_index}.png"))));
+        }
+    }
+}
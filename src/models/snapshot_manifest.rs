@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// An original image as it was before approval changed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub original: String,
+    /// Where the previous version of the original was archived, `None` when the original did
+    /// not exist before being approved.
+    pub backup: Option<String>,
+}
+
+/// Describes a snapshot of original images archived before an approval, so that it can be
+/// rolled back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub id: String,
+    /// Milliseconds since the unix epoch at which the snapshot was taken.
+    pub created_at_ms: u128,
+    pub entries: Vec<SnapshotEntry>,
+}
@@ -0,0 +1,41 @@
+/// The dpi svg lengths in physical units, such as `in` or `mm`, are converted to pixels at.
+const DEFAULT_SVG_DPI: f32 = 96_f32;
+
+/// How svg images are rasterised before being compared, as they have no pixels of their own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    scale: f32,
+    dpi: f32,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            scale: 1_f32,
+            dpi: DEFAULT_SVG_DPI,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// How many pixels each svg user unit is rasterised to, so a `24x24` icon at a scale of 2 is
+    /// rasterised to `48x48` pixels.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The dpi lengths in physical units are converted to user units at, before being scaled.
+    pub fn with_dpi(mut self, dpi: f32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    pub fn get_scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn get_dpi(&self) -> f32 {
+        self.dpi
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    config::CompareConfig,
+    errors::ivc::IVCError,
+    logger::Logger,
+    models::{PlannedPair, RunPlan},
+    utils::{
+        file_paths::{get_file_path_pairs_if_valid, get_unpaired_file_paths},
+        file_system::{
+            directories::get_directories_if_exist,
+            files::{
+                get_files_for_directories, get_files_if_directories_match_in_file_count,
+                get_filtered_files_for_directories,
+            },
+        },
+    },
+};
+
+/// Works out what a run would do with the images found via the config, without decoding any of
+/// them.
+///
+/// Only missing directories return an error, any other reason the run would fail, such as the
+/// images not being paired, is recorded as the plan's failure alongside the pairs that could be
+/// made.
+pub fn plan(config: impl Into<CompareConfig>) -> Result<RunPlan, IVCError> {
+    let config = config.into();
+    let (original_dir, latest_dir) = get_directories_if_exist(&config)?;
+
+    let image_pairs = get_files_if_directories_match_in_file_count(
+        &config,
+        original_dir.clone(),
+        latest_dir.clone(),
+    )
+    .and_then(|(orig_image_file_paths, latest_images_file_paths)| {
+        get_file_path_pairs_if_valid(&config, orig_image_file_paths, latest_images_file_paths)
+    });
+
+    let (orig_image_file_paths, latest_images_file_paths) =
+        get_files_for_directories(&config, &original_dir, &latest_dir);
+    let (new_images, missing_images) =
+        get_unpaired_file_paths(&config, &orig_image_file_paths, &latest_images_file_paths);
+
+    let (image_pairs, failure) = match image_pairs {
+        Ok(image_pairs) => (image_pairs, None),
+        Err(err) => {
+            let paired_orig_file_paths = orig_image_file_paths
+                .into_iter()
+                .filter(|location| !missing_images.contains(location))
+                .collect();
+            let paired_latest_file_paths = latest_images_file_paths
+                .into_iter()
+                .filter(|location| !new_images.contains(location))
+                .collect();
+            let image_pairs = get_file_path_pairs_if_valid(
+                &config,
+                paired_orig_file_paths,
+                paired_latest_file_paths,
+            )
+            .unwrap_or_default();
+
+            (image_pairs, Some(err.to_string()))
+        }
+    };
+
+    let run_plan = RunPlan {
+        pairs: image_pairs
+            .into_iter()
+            .map(|(original, latest)| PlannedPair { original, latest })
+            .collect(),
+        new_images,
+        missing_images,
+        filtered_files: get_filtered_files_for_directories(&config, &original_dir, &latest_dir),
+        failure,
+    };
+
+    Logger::info(format!(
+        "Planned run - pairs: '{}', new images: '{}', missing images: '{}', filtered files: '{}'",
+        run_plan.pairs.len(),
+        run_plan.new_images.len(),
+        run_plan.missing_images.len(),
+        run_plan.filtered_files.len()
+    ));
+
+    Ok(run_plan)
+}
+
+#[cfg(test)]
+mod tests {
+    mod plan {
+        use std::fs::{self, File};
+
+        use crate::{
+            config::CompareConfig, models::PlannedPair, plan::plan,
+            test_utils::files::create_temp_dir_handler,
+        };
+
+        #[test]
+        fn lists_pairs_unpaired_images_and_filtered_files_without_decoding_images() {
+            let temp_dir_handler = create_temp_dir_handler();
+            for dir in ["original", "latest"] {
+                fs::create_dir_all(temp_dir_handler.get_location_of_file_name(dir)).unwrap();
+            }
+            // the files are empty, so any attempt to decode them would fail
+            for file_name in [
+                "original/same.png",
+                "latest/same.png",
+                "original/removed.png",
+                "latest/added.png",
+                "latest/notes.txt",
+            ] {
+                File::create(temp_dir_handler.get_location_of_file_name(file_name)).unwrap();
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .build();
+
+            let run_plan = plan(config.clone()).unwrap();
+
+            let original_dir = config.get_original_images_dir();
+            let latest_dir = config.get_latest_images_dir();
+            assert_eq!(
+                vec![PlannedPair {
+                    original: format!("{original_dir}/same.png"),
+                    latest: format!("{latest_dir}/same.png"),
+                }],
+                run_plan.pairs
+            );
+            assert_eq!(vec![format!("{latest_dir}/added.png")], run_plan.new_images);
+            assert_eq!(
+                vec![format!("{original_dir}/removed.png")],
+                run_plan.missing_images
+            );
+            assert_eq!(
+                vec![format!("{latest_dir}/notes.txt")],
+                run_plan.filtered_files
+            );
+            assert_eq!(
+                Some("Not all images are paired up between original and latest. Please confirm image names are the same within the original and latest directories.".to_string()),
+                run_plan.failure
+            );
+        }
+
+        #[test]
+        fn has_no_failure_when_every_image_is_paired() {
+            let temp_dir_handler = create_temp_dir_handler();
+            for dir in ["original", "latest"] {
+                fs::create_dir_all(temp_dir_handler.get_location_of_file_name(dir)).unwrap();
+                File::create(temp_dir_handler.get_location_of_file_name(&format!("{dir}/a.png")))
+                    .unwrap();
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .build();
+
+            let run_plan = plan(config).unwrap();
+
+            assert_eq!(1, run_plan.pairs.len());
+            assert_eq!(None, run_plan.failure);
+        }
+
+        #[test]
+        fn returns_error_when_directories_are_missing() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .build();
+
+            assert!(plan(config).is_err());
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    config::CompareConfig,
+    errors::{handling::create_snapshot_not_found_error, ivc::IVCError},
+    logger::Logger,
+    models::SnapshotManifest,
+    utils::file_system::history::{get_snapshot_ids, read_snapshot_manifest, restore_snapshot},
+};
+
+/// Returns every snapshot of original images archived by approvals, oldest first.
+pub fn list_snapshots(config: impl Into<CompareConfig>) -> Result<Vec<SnapshotManifest>, IVCError> {
+    let history_dir = config.into().get_history_dir();
+
+    get_snapshot_ids(&history_dir)
+        .iter()
+        .map(|id| read_snapshot_manifest(&history_dir, id))
+        .collect()
+}
+
+/// Restores the original images archived in the snapshot, or in the most recent snapshot when
+/// none is chosen, undoing the approval that archived them.
+pub fn rollback(
+    config: impl Into<CompareConfig>,
+    snapshot: Option<&str>,
+) -> Result<SnapshotManifest, IVCError> {
+    let history_dir = config.into().get_history_dir();
+
+    let id = match snapshot {
+        Some(id) => id.to_owned(),
+        None => get_snapshot_ids(&history_dir)
+            .pop()
+            .ok_or(create_snapshot_not_found_error("latest"))?,
+    };
+
+    let manifest = read_snapshot_manifest(&history_dir, &id)?;
+    restore_snapshot(&manifest)?;
+
+    for entry in manifest.entries.iter() {
+        match &entry.backup {
+            Some(backup) => Logger::info(format!(
+                "Rolled back: restored '{}' from '{backup}'",
+                entry.original
+            )),
+            None => Logger::info(format!("Rolled back: removed '{}'", entry.original)),
+        }
+    }
+
+    Logger::info(format!(
+        "Rollback to snapshot '{id}' complete - number of baselines restored: '{}'",
+        manifest.entries.len()
+    ));
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    mod rollback {
+        use std::{fs, path::Path};
+
+        use crate::{
+            approve::approve,
+            config::ApproveOptions,
+            models::{PairStatus, RunResults},
+            rollback::{list_snapshots, rollback},
+            test_utils::{
+                config::create_config_for_test, files::create_temp_dir_handler,
+                results::create_pair_result,
+            },
+            utils::file_system::results::write_run_results,
+        };
+
+        #[test]
+        fn undoes_the_most_recent_approval() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let location = |name: &str| temp_dir_handler.get_location_of_file_name(name);
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+            for dir in ["original", "latest"] {
+                fs::create_dir_all(location(dir)).unwrap();
+            }
+            fs::write(location("original/a.png"), "original").unwrap();
+            fs::write(location("latest/a.png"), "latest").unwrap();
+            fs::write(location("latest/b.png"), "latest").unwrap();
+            let run_results = RunResults {
+                pairs: vec![create_pair_result(
+                    &location("original/a.png"),
+                    &location("latest/a.png"),
+                    PairStatus::Mismatched,
+                )],
+                new_images: vec![location("latest/b.png")],
+                missing_images: vec![],
+            };
+            write_run_results(&config.get_results_file_location(), &run_results).unwrap();
+
+            approve(config.clone(), &ApproveOptions::default()).unwrap();
+            assert_eq!(
+                "latest",
+                fs::read_to_string(location("original/a.png")).unwrap()
+            );
+
+            let snapshots = list_snapshots(config.clone()).unwrap();
+            let manifest = rollback(config, None).unwrap();
+
+            assert_eq!(vec![manifest.to_owned()], snapshots);
+            assert_eq!(2, manifest.entries.len());
+            assert_eq!(
+                "original",
+                fs::read_to_string(location("original/a.png")).unwrap()
+            );
+            assert!(!Path::new(&location("original/b.png")).exists());
+        }
+
+        #[test]
+        fn returns_error_when_there_are_no_snapshots() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+
+            let result = rollback(config, None);
+
+            assert_eq!(
+                "Could not find baseline snapshot: 'latest'.",
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+}
@@ -0,0 +1,557 @@
+use std::{
+    fs, io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use image::ImageFormat;
+use serde::Deserialize;
+use tokio::{net::TcpListener, runtime::Runtime};
+
+use crate::{
+    approve::{apply_baseline_changes, get_pair_baseline_changes},
+    config::CompareConfig,
+    errors::{handling::create_serve_error, ivc::IVCError},
+    logger::Logger,
+    models::{PairStatus, ReviewDecision, RunResults},
+    utils::{
+        file_paths::{get_file_location, is_svg_location},
+        file_system::results::{read_run_results, write_run_outputs},
+        report::render_review_page,
+    },
+};
+
+// bytes of randomness in a review token, which is twice as many characters once hex encoded
+const REVIEW_TOKEN_LENGTH: usize = 32;
+
+/// Serves a page on localhost for reviewing the last run's results, where each mismatched pair
+/// can be approved, promoting its latest image to be the original, or rejected.
+///
+/// Each decision is recorded in the results file and html report. Only requests addressed to
+/// localhost are answered, and decisions must carry the token of the page served, so that other
+/// sites open in the browser cannot make them. Runs until stopped.
+pub fn serve(config: impl Into<CompareConfig>, port: u16) -> Result<(), IVCError> {
+    let config = config.into();
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let to_serve_error = |error| create_serve_error(&address.to_string(), error);
+
+    let rt = Runtime::new().map_err(to_serve_error)?;
+
+    rt.block_on(async {
+        let listener = TcpListener::bind(address).await.map_err(to_serve_error)?;
+        // the port may have been assigned by the operating system, such as for port 0
+        let bound_address = listener.local_addr().map_err(to_serve_error)?;
+        let token = create_review_token().map_err(to_serve_error)?;
+
+        Logger::info(format!(
+            "Serving review of last run at: 'http://{bound_address}'"
+        ));
+
+        axum::serve(
+            listener,
+            create_review_router(config, bound_address.port(), token),
+        )
+        .await
+        .map_err(to_serve_error)
+    })
+}
+
+struct ReviewState {
+    config: CompareConfig,
+    allowed_hosts: [String; 2],
+    token: String,
+    // decisions read, update and write the results file, so are made one at a time
+    results_lock: Mutex<()>,
+}
+
+#[derive(Deserialize)]
+struct ImageQuery {
+    location: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewForm {
+    latest: String,
+    token: String,
+}
+
+type ErrorResponse = (StatusCode, String);
+
+/// Creates the routes for the review page, its images and the approve/reject actions.
+///
+/// Requests are only answered when addressed to localhost on the port, guarding against dns
+/// rebinding, and approve/reject requests must include the token, which the review page's forms
+/// carry, guarding against forms posted from other sites.
+pub fn create_review_router(config: CompareConfig, port: u16, token: String) -> Router {
+    let state = Arc::new(ReviewState {
+        config,
+        allowed_hosts: [format!("localhost:{port}"), format!("127.0.0.1:{port}")],
+        token,
+        results_lock: Mutex::new(()),
+    });
+
+    Router::new()
+        .route("/", get(show_review_page))
+        .route("/image", get(show_image))
+        .route("/approve", post(approve_pair))
+        .route("/reject", post(reject_pair))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            reject_other_hosts,
+        ))
+        .with_state(state)
+}
+
+/// Creates a random token for a review server, hex encoded from the operating system's secure
+/// random number generator.
+pub fn create_review_token() -> io::Result<String> {
+    let mut bytes = [0; REVIEW_TOKEN_LENGTH];
+    getrandom::getrandom(&mut bytes)?;
+
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+async fn reject_other_hosts(
+    State(state): State<Arc<ReviewState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ErrorResponse> {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok());
+
+    match host {
+        Some(host) if state.allowed_hosts.iter().any(|allowed| allowed == host) => {
+            Ok(next.run(request).await)
+        }
+        _ => Err((
+            StatusCode::FORBIDDEN,
+            format!("Host not allowed: '{}'", host.unwrap_or_default()),
+        )),
+    }
+}
+
+async fn show_review_page(
+    State(state): State<Arc<ReviewState>>,
+) -> Result<Html<String>, ErrorResponse> {
+    let run_results = read_results(&state.config)?;
+
+    Ok(Html(render_review_page(&run_results, &state.token)))
+}
+
+/// Only serves images referenced by the results, rather than any file the server can read.
+async fn show_image(
+    State(state): State<Arc<ReviewState>>,
+    Query(query): Query<ImageQuery>,
+) -> Result<Response, ErrorResponse> {
+    let run_results = read_results(&state.config)?;
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            format!("Image not found: '{}'", query.location),
+        )
+    };
+
+    if !is_referenced_by_results(&run_results, &query.location) {
+        return Err(not_found());
+    }
+
+    // a page of a multi-page image is served as its whole file
+    let file_location = get_file_location(&query.location);
+    let content = fs::read(file_location).map_err(|_| not_found())?;
+    let content_type = match ImageFormat::from_path(file_location) {
+        Ok(ImageFormat::Png) => "image/png",
+        Ok(ImageFormat::Jpeg) => "image/jpeg",
+        Ok(ImageFormat::WebP) => "image/webp",
+        Ok(ImageFormat::Gif) => "image/gif",
+        Ok(ImageFormat::Bmp) => "image/bmp",
+        Ok(ImageFormat::Tiff) => "image/tiff",
+        Err(_) if is_svg_location(file_location) => "image/svg+xml",
+        _ => "application/octet-stream",
+    };
+
+    // images, such as svgs with scripts, are kept from running as the server's origin
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_SECURITY_POLICY, "sandbox"),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+async fn approve_pair(
+    State(state): State<Arc<ReviewState>>,
+    Form(form): Form<ReviewForm>,
+) -> Result<Redirect, ErrorResponse> {
+    review_pair(&state, &form, ReviewDecision::Approved)?;
+
+    Ok(Redirect::to("/"))
+}
+
+async fn reject_pair(
+    State(state): State<Arc<ReviewState>>,
+    Form(form): Form<ReviewForm>,
+) -> Result<Redirect, ErrorResponse> {
+    review_pair(&state, &form, ReviewDecision::Rejected)?;
+
+    Ok(Redirect::to("/"))
+}
+
+fn review_pair(
+    state: &ReviewState,
+    form: &ReviewForm,
+    decision: ReviewDecision,
+) -> Result<(), ErrorResponse> {
+    if form.token != state.token {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Review token does not match the served page".to_string(),
+        ));
+    }
+
+    let latest = form.latest.as_str();
+    let _results_guard = state
+        .results_lock
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut run_results = read_results(&state.config)?;
+
+    let pair = run_results
+        .pairs
+        .iter_mut()
+        .find(|pair| pair.latest == latest && pair.status == PairStatus::Mismatched)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No mismatched pair for latest image: '{latest}'"),
+        ))?;
+
+    if pair.review.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Pair has already been reviewed: '{latest}'"),
+        ));
+    }
+
+    if decision == ReviewDecision::Approved {
+        let baseline_changes = get_pair_baseline_changes(&state.config, pair);
+        apply_baseline_changes(&state.config, &baseline_changes).map_err(to_error_response)?;
+    }
+
+    pair.review = Some(decision);
+    Logger::info(format!("Reviewed: '{latest}' - '{decision:?}'"));
+
+    write_run_outputs(&state.config, &run_results).map_err(to_error_response)
+}
+
+fn read_results(config: &CompareConfig) -> Result<RunResults, ErrorResponse> {
+    read_run_results(&config.get_results_file_location()).map_err(to_error_response)
+}
+
+fn is_referenced_by_results(run_results: &RunResults, location: &str) -> bool {
+    run_results.pairs.iter().any(|pair| {
+        pair.original == location
+            || pair.latest == location
+            || pair
+                .get_written_locations()
+                .any(|written_location| written_location == location)
+    }) || run_results.new_images.iter().any(|image| image == location)
+        || run_results
+            .missing_images
+            .iter()
+            .any(|image| image == location)
+}
+
+fn to_error_response(err: IVCError) -> ErrorResponse {
+    Logger::error(err.to_string());
+
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_review_token {
+        use crate::serve::create_review_token;
+
+        #[test]
+        fn creates_a_different_hex_token_each_time() {
+            let token = create_review_token().unwrap();
+
+            assert_eq!(64, token.len());
+            assert!(token.chars().all(|character| character.is_ascii_hexdigit()));
+            assert_ne!(token, create_review_token().unwrap());
+        }
+    }
+
+    mod create_review_router {
+        use std::{fs, path::Path};
+
+        use axum::{
+            body::{to_bytes, Body},
+            http::{header, Request, StatusCode},
+        };
+        use tower::ServiceExt;
+
+        use crate::{
+            config::CompareConfig,
+            models::{MismatchRegion, RegionCrop, ReviewDecision},
+            serve::create_review_router,
+            test_utils::results::setup_last_run,
+            utils::{
+                file_system::results::{read_run_results, write_run_results},
+                report::encode_url_value,
+            },
+        };
+
+        const PORT: u16 = 8080;
+        const TOKEN: &str = "abc123";
+
+        fn create_router(config: CompareConfig) -> axum::Router {
+            create_review_router(config, PORT, TOKEN.to_string())
+        }
+
+        fn get_request(uri: &str) -> Request<Body> {
+            Request::get(uri)
+                .header(header::HOST, format!("localhost:{PORT}"))
+                .body(Body::empty())
+                .unwrap()
+        }
+
+        fn form_request_with_token(action: &str, latest: &str, token: &str) -> Request<Body> {
+            Request::post(action)
+                .header(header::HOST, format!("127.0.0.1:{PORT}"))
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(format!(
+                    "latest={}&token={token}",
+                    encode_url_value(latest)
+                )))
+                .unwrap()
+        }
+
+        fn form_request(action: &str, latest: &str) -> Request<Body> {
+            form_request_with_token(action, latest, TOKEN)
+        }
+
+        #[tokio::test]
+        async fn shows_review_page_for_last_run() {
+            let (_temp_dir_handler, config) = setup_last_run();
+
+            let response = create_router(config)
+                .oneshot(get_request("/"))
+                .await
+                .unwrap();
+
+            assert_eq!(StatusCode::OK, response.status());
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let html = String::from_utf8(body.to_vec()).unwrap();
+            assert!(html.contains("<p>Pairs: 3, mismatched: 2, new: 1, missing: 1</p>"));
+            assert_eq!(2, html.matches("action=\"/approve\"").count());
+            assert!(html.contains(&format!("name=\"token\" value=\"{TOKEN}\"")));
+        }
+
+        #[tokio::test]
+        async fn rejects_requests_addressed_to_other_hosts() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let latest = temp_dir_handler.get_location_of_file_name("latest/home.png");
+
+            for host in ["evil.example:8080", "localhost:9090"] {
+                let mut request = form_request("/approve", &latest);
+                request
+                    .headers_mut()
+                    .insert(header::HOST, host.parse().unwrap());
+
+                let response = create_router(config.clone())
+                    .oneshot(request)
+                    .await
+                    .unwrap();
+
+                assert_eq!(StatusCode::FORBIDDEN, response.status());
+            }
+            assert_eq!(
+                "original",
+                fs::read_to_string(temp_dir_handler.get_location_of_file_name("original/home.png"))
+                    .unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_decisions_without_the_server_token() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let latest = temp_dir_handler.get_location_of_file_name("latest/home.png");
+
+            let response = create_router(config.clone())
+                .oneshot(form_request_with_token("/approve", &latest, "guessed"))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+            let response = create_router(config.clone())
+                .oneshot(
+                    Request::post("/reject")
+                        .header(header::HOST, format!("localhost:{PORT}"))
+                        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                        .body(Body::from(format!("latest={}", encode_url_value(&latest))))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert!(response.status().is_client_error());
+
+            assert_eq!(
+                "original",
+                fs::read_to_string(temp_dir_handler.get_location_of_file_name("original/home.png"))
+                    .unwrap()
+            );
+            let run_results = read_run_results(&config.get_results_file_location()).unwrap();
+            assert_eq!(None, run_results.pairs[1].review);
+        }
+
+        #[tokio::test]
+        async fn approving_pair_updates_original_and_results() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let latest = temp_dir_handler.get_location_of_file_name("latest/home.png");
+            let original = temp_dir_handler.get_location_of_file_name("original/home.png");
+
+            let response = create_router(config.clone())
+                .oneshot(form_request("/approve", &latest))
+                .await
+                .unwrap();
+
+            assert_eq!(StatusCode::SEE_OTHER, response.status());
+            assert_eq!("latest", fs::read_to_string(&original).unwrap());
+            let run_results = read_run_results(&config.get_results_file_location()).unwrap();
+            assert_eq!(Some(ReviewDecision::Approved), run_results.pairs[1].review);
+            assert!(Path::new(&config.get_history_dir()).is_dir());
+            assert!(Path::new(&config.get_report_file_location()).is_file());
+        }
+
+        #[tokio::test]
+        async fn rejecting_pair_keeps_original_and_records_decision() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let latest = temp_dir_handler.get_location_of_file_name("latest/home.png");
+
+            let response = create_router(config.clone())
+                .oneshot(form_request("/reject", &latest))
+                .await
+                .unwrap();
+
+            assert_eq!(StatusCode::SEE_OTHER, response.status());
+            assert_eq!(
+                "original",
+                fs::read_to_string(temp_dir_handler.get_location_of_file_name("original/home.png"))
+                    .unwrap()
+            );
+            let run_results = read_run_results(&config.get_results_file_location()).unwrap();
+            assert_eq!(Some(ReviewDecision::Rejected), run_results.pairs[1].review);
+
+            let response = create_router(config)
+                .oneshot(form_request("/approve", &latest))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::CONFLICT, response.status());
+        }
+
+        #[tokio::test]
+        async fn returns_not_found_for_matched_or_unknown_pairs() {
+            let (temp_dir_handler, config) = setup_last_run();
+
+            for latest in [
+                temp_dir_handler.get_location_of_file_name("latest/same.png"),
+                temp_dir_handler.get_location_of_file_name("latest/unknown.png"),
+            ] {
+                let response = create_router(config.clone())
+                    .oneshot(form_request("/approve", &latest))
+                    .await
+                    .unwrap();
+
+                assert_eq!(StatusCode::NOT_FOUND, response.status());
+            }
+        }
+
+        #[tokio::test]
+        async fn serves_only_images_referenced_by_results() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let image_request = |location: &str| {
+                get_request(&format!("/image?location={}", encode_url_value(location)))
+            };
+
+            let response = create_router(config.clone())
+                .oneshot(image_request(
+                    &temp_dir_handler.get_location_of_file_name("latest/home.png"),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("image/png", response.headers()[header::CONTENT_TYPE]);
+            assert_eq!(
+                "sandbox",
+                response.headers()[header::CONTENT_SECURITY_POLICY]
+            );
+            assert_eq!(
+                "nosniff",
+                response.headers()[header::X_CONTENT_TYPE_OPTIONS]
+            );
+
+            let response = create_router(config.clone())
+                .oneshot(image_request(&config.get_results_file_location()))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::NOT_FOUND, response.status());
+        }
+
+        #[tokio::test]
+        async fn serves_images_written_for_pairs() {
+            let (temp_dir_handler, config) = setup_last_run();
+            let location = |name: &str| temp_dir_handler.get_location_of_file_name(name);
+            let blink = location("mismatched/home.blink.gif");
+            let crop = RegionCrop {
+                region: MismatchRegion {
+                    x: 0,
+                    y: 0,
+                    width: 1,
+                    height: 1,
+                },
+                original: location("mismatched/home.region1.original.png"),
+                latest: location("mismatched/home.region1.latest.png"),
+                diff: location("mismatched/home.region1.diff.png"),
+            };
+            fs::create_dir_all(location("mismatched")).unwrap();
+            for written_location in [&blink, &crop.original, &crop.latest, &crop.diff] {
+                fs::write(written_location, "written").unwrap();
+            }
+            let mut run_results = read_run_results(&config.get_results_file_location()).unwrap();
+            run_results.pairs[1].blink = Some(blink.to_owned());
+            run_results.pairs[1].crops = vec![crop.to_owned()];
+            write_run_results(&config.get_results_file_location(), &run_results).unwrap();
+
+            for (written_location, content_type) in [
+                (&blink, "image/gif"),
+                (&crop.original, "image/png"),
+                (&crop.latest, "image/png"),
+                (&crop.diff, "image/png"),
+            ] {
+                let response = create_router(config.clone())
+                    .oneshot(get_request(&format!(
+                        "/image?location={}",
+                        encode_url_value(written_location)
+                    )))
+                    .await
+                    .unwrap();
+
+                assert_eq!(StatusCode::OK, response.status());
+                assert_eq!(content_type, response.headers()[header::CONTENT_TYPE]);
+            }
+        }
+    }
+}
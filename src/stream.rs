@@ -0,0 +1,451 @@
+use tokio::{sync::mpsc::Sender, task::JoinSet};
+
+use crate::{
+    compare::{compare_pair_of_images_and_frames, create_mismatched_image, is_pair_mismatched},
+    config::CompareConfig,
+    errors::{
+        handling::{create_dimension_mismatch_error, create_tokio_join_error},
+        ivc::IVCError,
+    },
+    models::ComparisonEvent,
+    utils::{
+        colour_profile::handle_colour_profiles,
+        file_paths::{
+            get_file_location, get_file_path_pairs_if_valid, get_mismatched_image_location,
+            get_unpaired_file_paths,
+        },
+        file_system::{
+            directories::get_directories_if_exist,
+            files::{get_files_for_directories, get_files_if_directories_match_in_file_count},
+            images::{get_pairs_of_pages_from_file_locations, save_svg_rasters_of_pair},
+        },
+        image::{
+            are_dimensions_matching_for_images, get_mismatch_percentage, get_mismatch_regions,
+        },
+    },
+};
+
+/// Runs the comparison for every image pair found via the config, sending a `ComparisonEvent`
+/// for each step of each pair.
+///
+/// Returns early once the receiving half of the channel has been dropped, which is how callers
+/// stop a run before every pair has been compared.
+pub async fn send_comparison_events(config: CompareConfig, sender: Sender<ComparisonEvent>) {
+    let (original_dir, latest_dir) = match get_directories_if_exist(&config) {
+        Ok(directories) => directories,
+        Err(err) => {
+            let _ = sender.send(ComparisonEvent::Failed(err)).await;
+            return;
+        }
+    };
+
+    let image_pairs = match get_image_pairs(&config, &original_dir, &latest_dir) {
+        Ok(image_pairs) => image_pairs,
+        Err(err) => {
+            let (orig_image_file_paths, latest_images_file_paths) =
+                get_files_for_directories(&config, &original_dir, &latest_dir);
+            let (new_images, missing_images) =
+                get_unpaired_file_paths(&config, &orig_image_file_paths, &latest_images_file_paths);
+
+            if !new_images.is_empty() || !missing_images.is_empty() {
+                let unpaired = ComparisonEvent::Unpaired {
+                    new_images,
+                    missing_images,
+                };
+                if sender.send(unpaired).await.is_err() {
+                    return;
+                }
+            }
+
+            let _ = sender.send(ComparisonEvent::Failed(err)).await;
+            return;
+        }
+    };
+
+    let latest_dir = config.get_latest_images_dir();
+    let mismatched_dir = config.get_mismatched_images_dir();
+    let is_keeping_extension = config.is_keeping_extensions_in_mismatched_names();
+
+    let mut retrieve_images_set = JoinSet::new();
+
+    for (orig_image_location, lat_image_location) in image_pairs.into_iter() {
+        let started = ComparisonEvent::Started {
+            original: orig_image_location.to_owned(),
+            latest: lat_image_location.to_owned(),
+        };
+        if sender.send(started).await.is_err() {
+            return;
+        }
+
+        let svg_options = config.get_svg_options();
+        let raster_dirs = config
+            .is_saving_svg_rasters()
+            .then(|| (latest_dir.to_owned(), mismatched_dir.to_owned()));
+        retrieve_images_set.spawn_blocking(move || {
+            let image_pairs = get_pairs_of_pages_from_file_locations(
+                &orig_image_location,
+                &lat_image_location,
+                &svg_options,
+            )?;
+            if let Some((latest_dir, mismatched_dir)) = raster_dirs {
+                for image_pair in image_pairs.iter() {
+                    let mismatched_location = get_mismatched_image_location(
+                        &image_pair.1.location,
+                        &latest_dir,
+                        &mismatched_dir,
+                        is_keeping_extension,
+                    );
+                    save_svg_rasters_of_pair(image_pair, &mismatched_location)?;
+                }
+            }
+
+            Ok(image_pairs)
+        });
+    }
+
+    let mut create_mismatch_images_set = JoinSet::new();
+
+    while let Some(tokio_join_result) = retrieve_images_set.join_next().await {
+        // multi-page images are compared page by page, any other pair on its own
+        let image_pairs =
+            match flatten_join_result(tokio_join_result, "retrieving images from file system") {
+                Ok(image_pairs) => image_pairs,
+                Err(err) => {
+                    if sender.send(ComparisonEvent::Failed(err)).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+        for image_pair in image_pairs.into_iter() {
+            if !are_dimensions_matching_for_images(&image_pair) {
+                let err = create_dimension_mismatch_error(image_pair);
+                if sender.send(ComparisonEvent::Failed(err)).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+
+            let compare_options =
+                config.get_compare_options_for(get_file_location(&image_pair.1.location));
+            let (image_pair, profiles) =
+                handle_colour_profiles(image_pair, compare_options.get_profiles());
+            let (mismatched_pixels, animation_comparison) =
+                compare_pair_of_images_and_frames(&image_pair, &compare_options);
+            let regions = get_mismatch_regions(&mismatched_pixels);
+
+            let compared = ComparisonEvent::Compared {
+                original: image_pair.0.location.to_owned(),
+                latest: image_pair.1.location.to_owned(),
+                mismatched_pixel_count: mismatched_pixels.len(),
+                mismatch_percentage: get_mismatch_percentage(
+                    mismatched_pixels.len(),
+                    image_pair.1.image.width() as u64 * image_pair.1.image.height() as u64,
+                ),
+                region_count: regions.len(),
+                animation: animation_comparison
+                    .as_ref()
+                    .map(|comparison| comparison.result.to_owned()),
+                profiles,
+            };
+            if sender.send(compared).await.is_err() {
+                return;
+            }
+
+            if is_pair_mismatched(&mismatched_pixels, &animation_comparison) {
+                let mismatched_location = get_mismatched_image_location(
+                    &image_pair.1.location,
+                    &latest_dir,
+                    &mismatched_dir,
+                    is_keeping_extension,
+                );
+                create_mismatch_images_set.spawn_blocking(move || {
+                    create_mismatched_image(
+                        image_pair,
+                        mismatched_pixels,
+                        regions,
+                        animation_comparison,
+                        &mismatched_location,
+                        &compare_options,
+                    )
+                });
+            }
+        }
+    }
+
+    while let Some(tokio_join_result) = create_mismatch_images_set.join_next().await {
+        let event = match flatten_join_result(tokio_join_result, "creating mismatch images") {
+            Ok(comparison_result) => ComparisonEvent::DiffWritten {
+                original: comparison_result.get_original_location().to_owned(),
+                latest: comparison_result.get_latest_location().to_owned(),
+                location: comparison_result
+                    .get_comparison_location()
+                    .unwrap_or_default()
+                    .to_owned(),
+                blink: comparison_result
+                    .get_blink_location()
+                    .map(|location| location.to_owned()),
+                crops: comparison_result.get_crops().to_vec(),
+            },
+            Err(err) => ComparisonEvent::Failed(err),
+        };
+
+        if sender.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn get_image_pairs(
+    config: &CompareConfig,
+    original_dir: &str,
+    latest_dir: &str,
+) -> Result<Vec<(String, String)>, IVCError> {
+    let (orig_image_file_paths, latest_images_file_paths) =
+        get_files_if_directories_match_in_file_count(
+            config,
+            original_dir.to_owned(),
+            latest_dir.to_owned(),
+        )?;
+
+    get_file_path_pairs_if_valid(config, orig_image_file_paths, latest_images_file_paths)
+}
+
+fn flatten_join_result<T>(
+    tokio_join_result: Result<Result<T, IVCError>, tokio::task::JoinError>,
+    action: &str,
+) -> Result<T, IVCError> {
+    match tokio_join_result {
+        Ok(task_result) => task_result,
+        Err(err) => Err(create_tokio_join_error(action, err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod send_comparison_events {
+        use tokio::sync::mpsc;
+
+        use crate::{
+            config::CompareConfig,
+            models::ComparisonEvent,
+            stream::send_comparison_events,
+            test_utils::{
+                config::create_config_for_test,
+                files::{create_temp_dir_handler, setup_image_directories},
+                image::{change_pixel_on_img, create_dynamic_image, save_multi_page_tiff},
+            },
+        };
+
+        #[tokio::test]
+        async fn sends_started_compared_and_diff_written_events_for_mismatched_pair() {
+            let temp_dir_handler = create_temp_dir_handler();
+            setup_image_directories(&temp_dir_handler);
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+
+            let (sender, mut receiver) = mpsc::channel(16);
+            send_comparison_events(config, sender).await;
+
+            let mut events = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                events.push(event);
+            }
+
+            let started_count = events
+                .iter()
+                .filter(|event| matches!(event, ComparisonEvent::Started { .. }))
+                .count();
+            let mismatched_counts: Vec<usize> = events
+                .iter()
+                .filter_map(|event| match event {
+                    ComparisonEvent::Compared {
+                        mismatched_pixel_count,
+                        ..
+                    } => Some(*mismatched_pixel_count),
+                    _ => None,
+                })
+                .collect();
+            let diff_locations: Vec<&String> = events
+                .iter()
+                .filter_map(|event| match event {
+                    ComparisonEvent::DiffWritten { location, .. } => Some(location),
+                    _ => None,
+                })
+                .collect();
+
+            assert_eq!(2, started_count);
+            assert_eq!(2, mismatched_counts.len());
+            assert!(mismatched_counts.contains(&0));
+            assert!(mismatched_counts.contains(&1));
+            assert_eq!(
+                vec![&temp_dir_handler.get_location_of_file_name("mismatched/changed.png")],
+                diff_locations
+            );
+            assert!(std::path::Path::new(diff_locations[0]).is_file());
+        }
+
+        #[tokio::test]
+        async fn writes_separate_diffs_for_latest_images_differing_only_by_extension() {
+            let temp_dir_handler = create_temp_dir_handler();
+            temp_dir_handler.create_dir_in_temp_dir("original");
+            temp_dir_handler.create_dir_in_temp_dir("latest");
+            let mut changed_image = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut changed_image, 3, 3);
+            for extension in ["png", "bmp"] {
+                create_dynamic_image(5, 5)
+                    .save(
+                        temp_dir_handler
+                            .get_location_of_file_name(&format!("original/foo.{extension}")),
+                    )
+                    .unwrap();
+                changed_image
+                    .save(
+                        temp_dir_handler
+                            .get_location_of_file_name(&format!("latest/foo.{extension}")),
+                    )
+                    .unwrap();
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .image_extensions(["png", "bmp"])
+                .build();
+
+            let (sender, mut receiver) = mpsc::channel(16);
+            send_comparison_events(config, sender).await;
+
+            let mut diff_locations = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                if let ComparisonEvent::DiffWritten { location, .. } = event {
+                    diff_locations.push(location);
+                }
+            }
+            diff_locations.sort();
+
+            assert_eq!(
+                vec![
+                    temp_dir_handler.get_location_of_file_name("mismatched/foo.bmp.png"),
+                    temp_dir_handler.get_location_of_file_name("mismatched/foo.png.png"),
+                ],
+                diff_locations
+            );
+            assert!(diff_locations
+                .iter()
+                .all(|location| std::path::Path::new(location).is_file()));
+        }
+
+        #[tokio::test]
+        async fn sends_failed_event_when_directories_are_missing() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+
+            let (sender, mut receiver) = mpsc::channel(16);
+            send_comparison_events(config, sender).await;
+
+            assert!(matches!(
+                receiver.recv().await,
+                Some(ComparisonEvent::Failed(_))
+            ));
+            assert!(receiver.recv().await.is_none());
+        }
+
+        #[tokio::test]
+        async fn sends_unpaired_event_before_failed_event_when_images_are_not_paired() {
+            let temp_dir_handler = create_temp_dir_handler();
+            setup_image_directories(&temp_dir_handler);
+            create_dynamic_image(5, 5)
+                .save(temp_dir_handler.get_location_of_file_name("latest/new.png"))
+                .unwrap();
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+
+            let (sender, mut receiver) = mpsc::channel(16);
+            send_comparison_events(config, sender).await;
+
+            match receiver.recv().await {
+                Some(ComparisonEvent::Unpaired {
+                    new_images,
+                    missing_images,
+                }) => {
+                    assert_eq!(
+                        vec![temp_dir_handler.get_location_of_file_name("latest/new.png")],
+                        new_images
+                    );
+                    assert!(missing_images.is_empty());
+                }
+                event => panic!("expected unpaired event, received '{:?}'", event),
+            }
+            assert!(matches!(
+                receiver.recv().await,
+                Some(ComparisonEvent::Failed(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn stops_sending_events_when_receiver_is_dropped() {
+            let temp_dir_handler = create_temp_dir_handler();
+            setup_image_directories(&temp_dir_handler);
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+
+            let (sender, receiver) = mpsc::channel(16);
+            drop(receiver);
+            send_comparison_events(config, sender).await;
+
+            assert!(!std::path::Path::new(
+                &temp_dir_handler.get_location_of_file_name("mismatched")
+            )
+            .exists());
+        }
+
+        #[tokio::test]
+        async fn compares_each_page_of_multi_page_tiffs_as_its_own_pair() {
+            let temp_dir_handler = create_temp_dir_handler();
+            temp_dir_handler.create_dir_in_temp_dir("original");
+            temp_dir_handler.create_dir_in_temp_dir("latest");
+            let mut changed_page = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut changed_page, 3, 3);
+            save_multi_page_tiff(
+                &[create_dynamic_image(5, 5), create_dynamic_image(5, 5)],
+                &temp_dir_handler.get_location_of_file_name("original/doc.tiff"),
+            );
+            save_multi_page_tiff(
+                &[create_dynamic_image(5, 5), changed_page],
+                &temp_dir_handler.get_location_of_file_name("latest/doc.tiff"),
+            );
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .image_extensions(["tiff"])
+                .build();
+
+            let (sender, mut receiver) = mpsc::channel(16);
+            send_comparison_events(config, sender).await;
+
+            let mut compared = Vec::new();
+            let mut diff_locations = Vec::new();
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    ComparisonEvent::Compared {
+                        latest,
+                        mismatched_pixel_count,
+                        ..
+                    } => compared.push((latest, mismatched_pixel_count)),
+                    ComparisonEvent::DiffWritten { location, .. } => diff_locations.push(location),
+                    _ => {}
+                }
+            }
+            compared.sort();
+
+            let latest = temp_dir_handler.get_location_of_file_name("latest/doc.tiff");
+            assert_eq!(
+                vec![
+                    (format!("{latest}#page1"), 0),
+                    (format!("{latest}#page2"), 1)
+                ],
+                compared
+            );
+            assert_eq!(
+                vec![temp_dir_handler.get_location_of_file_name("mismatched/doc.tiff.page2.png")],
+                diff_locations
+            );
+        }
+    }
+}
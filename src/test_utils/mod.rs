@@ -23,6 +23,44 @@ pub mod image {
         pixel[3] = 255;
         image.put_pixel(3, 3, pixel);
     }
+
+    /// Writes each image as a page of a single tiff file.
+    pub fn save_multi_page_tiff(pages: &[DynamicImage], location: &str) {
+        let file = std::fs::File::create(location).unwrap();
+        let mut encoder = tiff::encoder::TiffEncoder::new(file).unwrap();
+
+        for page in pages.iter() {
+            encoder
+                .write_image::<tiff::encoder::colortype::RGBA8>(
+                    page.width(),
+                    page.height(),
+                    page.to_rgba8().as_raw(),
+                )
+                .unwrap();
+        }
+    }
+
+    /// A v4 sRGB icc profile, described as `sRGB`.
+    pub const SRGB_ICC_PROFILE: &[u8] = include_bytes!("fixtures/srgb.icc");
+    /// A v4 icc profile, described as `Swapped`, with sRGB's red and blue colorants swapped, so
+    /// pure red pixels are shown as pure blue.
+    pub const SWAPPED_ICC_PROFILE: &[u8] = include_bytes!("fixtures/swapped.icc");
+
+    /// Writes the image as an 8 bit rgba png with the icc profile embedded in an `iCCP` chunk.
+    pub fn save_png_with_icc_profile(image: &DynamicImage, icc_profile: &[u8], location: &str) {
+        let rgba_image = image.to_rgba8();
+        let file = std::fs::File::create(location).unwrap();
+        let mut encoder = png::Encoder::new(file, rgba_image.width(), rgba_image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+
+        // the profile's name, its terminator and the compression method, then the profile
+        let mut chunk = b"icc\0\0".to_vec();
+        chunk.extend(miniz_oxide::deflate::compress_to_vec_zlib(icc_profile, 6));
+        writer.write_chunk(png::chunk::iCCP, &chunk).unwrap();
+        writer.write_image_data(rgba_image.as_raw()).unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -42,6 +80,11 @@ pub mod files {
 
     use assert_fs::TempDir;
 
+    use super::{
+        constants::TestConstants,
+        image::{change_pixel_on_img, create_dynamic_image},
+    };
+
     pub struct TempDirHandler {
         pub temp_dir: Option<TempDir>,
     }
@@ -96,6 +139,39 @@ pub mod files {
         (image_one_location, image_two_location)
     }
 
+    /// Writes an original and latest directory to the temp dir, each with a 'same.png' that
+    /// matches and a 'changed.png' that differs by a pixel.
+    pub fn setup_image_directories(temp_dir_handler: &TempDirHandler) {
+        temp_dir_handler.create_dir_in_temp_dir(TestConstants::ORIGINAL);
+        temp_dir_handler.create_dir_in_temp_dir(TestConstants::LATEST);
+
+        let mut changed_image = create_dynamic_image(5, 5);
+        change_pixel_on_img(&mut changed_image, 3, 3);
+
+        for (dir, name, image) in [
+            (
+                TestConstants::ORIGINAL,
+                "same.png",
+                create_dynamic_image(5, 5),
+            ),
+            (
+                TestConstants::LATEST,
+                "same.png",
+                create_dynamic_image(5, 5),
+            ),
+            (
+                TestConstants::ORIGINAL,
+                "changed.png",
+                create_dynamic_image(5, 5),
+            ),
+            (TestConstants::LATEST, "changed.png", changed_image),
+        ] {
+            image
+                .save(temp_dir_handler.get_location_of_file_name(&format!("{dir}/{name}")))
+                .unwrap();
+        }
+    }
+
     pub fn create_file_names(base: &str) -> Vec<String> {
         vec![
             format!("{:}/another_dir/more_image.png", base),
@@ -108,22 +184,85 @@ pub mod files {
 
 #[cfg(test)]
 pub mod config {
-    use std::ffi::OsString;
+    use crate::config::CompareConfig;
 
-    use clap::Parser;
+    pub fn create_config_for_test(directory: &str) -> CompareConfig {
+        CompareConfig::builder().directory(directory).build()
+    }
+}
 
-    use crate::config::AppConfig;
+#[cfg(test)]
+pub mod results {
+    use std::fs;
 
-    pub fn create_config_for_test(directory: &str) -> AppConfig {
-        let app_name = "ivc";
+    use crate::{
+        config::CompareConfig,
+        models::{PairResult, PairStatus, RunResults},
+        utils::file_system::results::write_run_results,
+    };
 
-        let input = vec![
-            OsString::from(app_name),
-            OsString::from("--directory"),
-            OsString::from(directory),
-        ]
-        .into_iter();
+    use super::{
+        config::create_config_for_test,
+        files::{create_temp_dir_handler, TempDirHandler},
+    };
+
+    /// Creates the result of a pair, with one mismatched pixel when mismatched and no diff or
+    /// other details, for tests to fill in what they need with struct update syntax.
+    pub fn create_pair_result(original: &str, latest: &str, status: PairStatus) -> PairResult {
+        PairResult {
+            original: original.to_string(),
+            latest: latest.to_string(),
+            status,
+            mismatched_pixel_count: match status {
+                PairStatus::Matched => 0,
+                PairStatus::Mismatched => 1,
+            },
+            mismatch_percentage: None,
+            region_count: None,
+            diff: None,
+            review: None,
+            animation: None,
+            profiles: None,
+            blink: None,
+            crops: Vec::new(),
+        }
+    }
+
+    /// Writes the results of a last run to a temp dir, with two mismatched pairs, one matched
+    /// pair, a new image and a missing image, each with its files written as text.
+    pub fn setup_last_run() -> (TempDirHandler, CompareConfig) {
+        let temp_dir_handler = create_temp_dir_handler();
+        let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+        let location = |name: &str| temp_dir_handler.get_location_of_file_name(name);
+
+        for dir in ["original/checkout", "latest/checkout"] {
+            fs::create_dir_all(location(dir)).unwrap();
+        }
+        for name in ["checkout/cart.png", "home.png", "same.png", "gone.png"] {
+            fs::write(location(&format!("original/{name}")), "original").unwrap();
+        }
+        for name in ["checkout/cart.png", "home.png", "same.png", "new.png"] {
+            fs::write(location(&format!("latest/{name}")), "latest").unwrap();
+        }
+
+        let pair = |name: &str, status: PairStatus| {
+            create_pair_result(
+                &location(&format!("original/{name}")),
+                &location(&format!("latest/{name}")),
+                status,
+            )
+        };
+        let run_results = RunResults {
+            pairs: vec![
+                pair("checkout/cart.png", PairStatus::Mismatched),
+                pair("home.png", PairStatus::Mismatched),
+                pair("same.png", PairStatus::Matched),
+            ],
+            new_images: vec![location("latest/new.png")],
+            missing_images: vec![location("original/gone.png")],
+        };
+        write_run_results(&config.get_results_file_location(), &run_results).unwrap();
 
-        AppConfig::parse_from(input)
+        (temp_dir_handler, config)
     }
 }
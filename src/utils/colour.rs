@@ -1,7 +1,7 @@
-use image::{DynamicImage, GenericImageView};
+use image::{DynamicImage, GenericImageView, Rgba32FImage};
 use lab::Lab;
 
-use crate::models::PixelCoord;
+use crate::models::{ColourMetric, PixelCoord};
 
 pub fn get_lab_colour_for_img_pixel(img: &DynamicImage, pixel_coord: &PixelCoord) -> Lab {
     let pixel = img.get_pixel(pixel_coord.x, pixel_coord.y);
@@ -9,6 +9,82 @@ pub fn get_lab_colour_for_img_pixel(img: &DynamicImage, pixel_coord: &PixelCoord
     Lab::from_rgba(&rgba_value)
 }
 
+/// Returns the difference between the pixels at the co-ordinate of each image, as measured by the
+/// metric.
+pub fn get_colour_difference_for_img_pixels(
+    img_one: &DynamicImage,
+    img_two: &DynamicImage,
+    pixel_coord: &PixelCoord,
+    metric: ColourMetric,
+) -> f32 {
+    match metric {
+        ColourMetric::LabSquared => get_lab_colour_for_img_pixel(img_one, pixel_coord)
+            .squared_distance(&get_lab_colour_for_img_pixel(img_two, pixel_coord)),
+        ColourMetric::Lab => get_lab_colour_for_img_pixel(img_one, pixel_coord)
+            .squared_distance(&get_lab_colour_for_img_pixel(img_two, pixel_coord))
+            .sqrt(),
+        ColourMetric::Rgba => {
+            let pixel_one = img_one.get_pixel(pixel_coord.x, pixel_coord.y).0;
+            let pixel_two = img_two.get_pixel(pixel_coord.x, pixel_coord.y).0;
+
+            pixel_one
+                .iter()
+                .zip(pixel_two.iter())
+                .map(|(channel_one, channel_two)| channel_one.abs_diff(*channel_two))
+                .max()
+                .unwrap_or_default() as f32
+        }
+    }
+}
+
+// differences at native precision are measured on the same scale as 8-bit channels
+const EIGHT_BIT_SCALE: f32 = 255_f32;
+
+/// Returns the difference between the pixels at the co-ordinate of each image, as measured by the
+/// metric, keeping the precision of images with more than 8 bits per channel.
+///
+/// The images are expected to be converted via `DynamicImage::to_rgba32f`, which scales integer
+/// channels to 0 - 1. The difference is on the same scale as `get_colour_difference_for_img_pixels`
+/// but keeps fractions of an 8-bit step. Lab colours are only defined for channels within 0 - 1,
+/// so channels are clamped for the lab metrics.
+pub fn get_native_colour_difference_for_img_pixels(
+    img_one: &Rgba32FImage,
+    img_two: &Rgba32FImage,
+    pixel_coord: &PixelCoord,
+    metric: ColourMetric,
+) -> f32 {
+    let pixel_one = img_one.get_pixel(pixel_coord.x, pixel_coord.y).0;
+    let pixel_two = img_two.get_pixel(pixel_coord.x, pixel_coord.y).0;
+    let get_lab_colour = |pixel: [f32; 4]| {
+        Lab::from_rgb_normalized(&[
+            pixel[0].clamp(0_f32, 1_f32),
+            pixel[1].clamp(0_f32, 1_f32),
+            pixel[2].clamp(0_f32, 1_f32),
+        ])
+    };
+
+    match metric {
+        ColourMetric::LabSquared => {
+            get_lab_colour(pixel_one).squared_distance(&get_lab_colour(pixel_two))
+        }
+        ColourMetric::Lab => get_lab_colour(pixel_one)
+            .squared_distance(&get_lab_colour(pixel_two))
+            .sqrt(),
+        ColourMetric::Rgba => pixel_one
+            .iter()
+            .zip(pixel_two.iter())
+            .map(|(channel_one, channel_two)| (channel_one - channel_two).abs() * EIGHT_BIT_SCALE)
+            .fold(0_f32, f32::max),
+    }
+}
+
+/// Whether the image has more than 8 bits per channel.
+pub fn is_high_bit_depth_image(img: &DynamicImage) -> bool {
+    let colour_type = img.color();
+
+    colour_type.bytes_per_pixel() > colour_type.channel_count()
+}
+
 #[cfg(test)]
 mod tests {
     use image::GenericImageView;
@@ -36,4 +112,46 @@ mod tests {
             actual, expected
         );
     }
+
+    mod get_colour_difference_for_img_pixels {
+        use image::{GenericImage, GenericImageView};
+
+        use crate::{
+            models::{ColourMetric, PixelCoord},
+            test_utils::image::create_dynamic_image,
+            utils::colour::get_colour_difference_for_img_pixels,
+        };
+
+        #[test]
+        fn measures_difference_using_each_metric() {
+            let img_one = create_dynamic_image(2, 2);
+            let mut img_two = create_dynamic_image(2, 2);
+            let mut pixel = img_two.get_pixel(1, 1);
+            pixel[1] += 20;
+            img_two.put_pixel(1, 1, pixel);
+            let pixel_coord = PixelCoord::new(1, 1);
+
+            let lab_squared = get_colour_difference_for_img_pixels(
+                &img_one,
+                &img_two,
+                &pixel_coord,
+                ColourMetric::LabSquared,
+            );
+            let lab = get_colour_difference_for_img_pixels(
+                &img_one,
+                &img_two,
+                &pixel_coord,
+                ColourMetric::Lab,
+            );
+            let rgba = get_colour_difference_for_img_pixels(
+                &img_one,
+                &img_two,
+                &pixel_coord,
+                ColourMetric::Rgba,
+            );
+
+            assert_eq!(lab_squared.sqrt(), lab);
+            assert_eq!(20_f32, rgba);
+        }
+    }
 }
@@ -0,0 +1,302 @@
+use image::DynamicImage;
+use qcms::{DataType, Intent, Profile, Transform};
+
+use crate::{
+    logger::Logger,
+    models::{ColourProfile, ImageHolder, ProfileHandling, ProfileResult},
+};
+
+const UNNAMED_PROFILE: &str = "unnamed";
+const NO_PROFILE: &str = "none";
+// rgba colours converted to sRGB to find whether a profile is sRGB, which leaves them unchanged
+const SRGB_SAMPLE_COLOURS: [[u8; 4]; 8] = [
+    [0, 0, 0, 255],
+    [16, 16, 16, 255],
+    [128, 128, 128, 255],
+    [255, 255, 255, 255],
+    [255, 0, 0, 255],
+    [0, 255, 0, 255],
+    [0, 0, 255, 255],
+    [64, 160, 224, 255],
+];
+// how far a converted sample colour's channels can move, from rounding, for a profile to be sRGB
+const SRGB_SAMPLE_TOLERANCE: u8 = 2;
+
+/// Creates the colour profile from the icc profile's data, named after its description.
+pub fn create_colour_profile(data: Vec<u8>) -> ColourProfile {
+    ColourProfile {
+        name: read_profile_description(&data).unwrap_or(UNNAMED_PROFILE.to_string()),
+        data,
+    }
+}
+
+/// Handles the colour profiles embedded in the pair's images, returning the pair to compare along
+/// with its profiles, when either image embeds one.
+///
+/// When converting, images with a profile other than sRGB, along with each of their frames, are
+/// converted to sRGB at 8 bits per channel, so that the lab colours of their pixels are
+/// comparable. Otherwise a warning is logged when the profiles differ, images without a profile
+/// matching those with an sRGB one.
+pub fn handle_colour_profiles(
+    (mut original_image, mut latest_image): (ImageHolder, ImageHolder),
+    handling: ProfileHandling,
+) -> ((ImageHolder, ImageHolder), Option<ProfileResult>) {
+    if handling == ProfileHandling::Ignore
+        || (original_image.profile.is_none() && latest_image.profile.is_none())
+    {
+        return ((original_image, latest_image), None);
+    }
+
+    let get_name = |image_holder: &ImageHolder| {
+        image_holder
+            .profile
+            .as_ref()
+            .map(|profile| profile.name.to_owned())
+    };
+    let mut profile_result = ProfileResult {
+        original: get_name(&original_image),
+        latest: get_name(&latest_image),
+        converted: false,
+    };
+
+    match handling {
+        ProfileHandling::Convert => {
+            let is_original_converted = convert_to_srgb(&mut original_image);
+            let is_latest_converted = convert_to_srgb(&mut latest_image);
+            profile_result.converted = is_original_converted || is_latest_converted;
+        }
+        _ if !is_matching_profiles(&original_image, &latest_image) => Logger::warn(format!(
+            "Colour profiles differ - original: '{}' has '{}', latest: '{}' has '{}'",
+            original_image.location,
+            profile_result.original.as_deref().unwrap_or(NO_PROFILE),
+            latest_image.location,
+            profile_result.latest.as_deref().unwrap_or(NO_PROFILE)
+        )),
+        _ => {}
+    }
+
+    ((original_image, latest_image), Some(profile_result))
+}
+
+/// Whether the images' profiles match, an image without a profile being treated as sRGB.
+fn is_matching_profiles(original_image: &ImageHolder, latest_image: &ImageHolder) -> bool {
+    let get_name = |image_holder: &ImageHolder| {
+        image_holder
+            .profile
+            .as_ref()
+            .map(|profile| profile.name.to_owned())
+    };
+
+    (is_srgb(original_image) && is_srgb(latest_image))
+        || get_name(original_image) == get_name(latest_image)
+}
+
+/// Whether the image has no profile, or one that converts sRGB colours to themselves.
+fn is_srgb(image_holder: &ImageHolder) -> bool {
+    image_holder.profile.as_ref().is_none_or(|colour_profile| {
+        create_srgb_transform(colour_profile).is_some_and(|transform| is_srgb_transform(&transform))
+    })
+}
+
+fn is_srgb_transform(transform: &Transform) -> bool {
+    let mut colours = SRGB_SAMPLE_COLOURS.concat();
+    transform.apply(&mut colours);
+
+    colours
+        .iter()
+        .zip(SRGB_SAMPLE_COLOURS.iter().flatten())
+        .all(|(converted, sample)| converted.abs_diff(*sample) <= SRGB_SAMPLE_TOLERANCE)
+}
+
+fn create_srgb_transform(colour_profile: &ColourProfile) -> Option<Transform> {
+    let profile = Profile::new_from_slice(&colour_profile.data, false)?;
+
+    Transform::new(
+        &profile,
+        &Profile::new_sRGB(),
+        DataType::RGBA8,
+        Intent::RelativeColorimetric,
+    )
+}
+
+/// Converts the image from its profile to sRGB, returning whether it was converted, which it is
+/// not when it has no profile, is already sRGB, or its profile cannot be used.
+fn convert_to_srgb(image_holder: &mut ImageHolder) -> bool {
+    let Some(colour_profile) = &image_holder.profile else {
+        return false;
+    };
+
+    let Some(transform) = create_srgb_transform(colour_profile) else {
+        Logger::warn(format!(
+            "Could not convert image to sRGB from colour profile: '{}' - image: '{}'",
+            colour_profile.name, image_holder.location
+        ));
+        return false;
+    };
+    if is_srgb_transform(&transform) {
+        return false;
+    }
+
+    image_holder.image = convert_image(&transform, &image_holder.image);
+    for frame in image_holder.frames.iter_mut() {
+        frame.image = convert_image(&transform, &frame.image);
+    }
+
+    true
+}
+
+fn convert_image(transform: &Transform, image: &DynamicImage) -> DynamicImage {
+    let mut rgba_image = image.to_rgba8();
+    transform.apply(&mut rgba_image);
+
+    DynamicImage::ImageRgba8(rgba_image)
+}
+
+/// Reads the description of the icc profile, from either a v2 `desc` or a v4 `mluc` tag.
+fn read_profile_description(data: &[u8]) -> Option<String> {
+    const TAG_TABLE_OFFSET: usize = 128;
+    const TAG_ENTRY_SIZE: usize = 12;
+
+    let tag_count = read_u32(data, TAG_TABLE_OFFSET)?;
+    let tag_entry = (0..tag_count)
+        .map(|index| TAG_TABLE_OFFSET + 4 + index * TAG_ENTRY_SIZE)
+        .take_while(|tag_entry| tag_entry + TAG_ENTRY_SIZE <= data.len())
+        .find(|tag_entry| &data[*tag_entry..tag_entry + 4] == b"desc")?;
+    let (offset, size) = (
+        read_u32(data, tag_entry + 4)?,
+        read_u32(data, tag_entry + 8)?,
+    );
+    let element = data.get(offset..offset.checked_add(size)?)?;
+
+    let description = match element.get(0..4)? {
+        b"desc" => {
+            let length = read_u32(element, 8)?;
+            String::from_utf8_lossy(element.get(12..12 + length)?).to_string()
+        }
+        b"mluc" => {
+            // the first record is used, whatever its language
+            let (length, offset) = (read_u32(element, 20)?, read_u32(element, 24)?);
+            let utf16: Vec<u16> = element
+                .get(offset..offset.checked_add(length)?)?
+                .chunks_exact(2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .collect();
+            String::from_utf16_lossy(&utf16)
+        }
+        _ => return None,
+    };
+    let description = description.trim_end_matches('\0').trim();
+
+    (!description.is_empty()).then(|| description.to_string())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<usize> {
+    let value = bytes.get(offset..offset.checked_add(4)?)?;
+
+    Some(u32::from_be_bytes([value[0], value[1], value[2], value[3]]) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_colour_profile {
+        use crate::{
+            test_utils::image::SRGB_ICC_PROFILE, utils::colour_profile::create_colour_profile,
+        };
+
+        #[test]
+        fn names_profile_after_its_description() {
+            let colour_profile = create_colour_profile(SRGB_ICC_PROFILE.to_vec());
+
+            assert_eq!("sRGB", colour_profile.name);
+        }
+
+        #[test]
+        fn names_profile_unnamed_when_it_has_no_description() {
+            let colour_profile = create_colour_profile(vec![0; 64]);
+
+            assert_eq!("unnamed", colour_profile.name);
+        }
+    }
+
+    mod handle_colour_profiles {
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        use crate::{
+            models::{ImageHolder, ProfileHandling, ProfileResult},
+            test_utils::image::{SRGB_ICC_PROFILE, SWAPPED_ICC_PROFILE},
+            utils::colour_profile::{
+                create_colour_profile, handle_colour_profiles, is_matching_profiles,
+            },
+        };
+
+        // red is stored as blue, so pure red pixels are shown as pure blue
+        fn create_swapped_pair() -> (ImageHolder, ImageHolder) {
+            let image =
+                |pixel: [u8; 4]| DynamicImage::ImageRgba8(RgbaImage::from_pixel(2, 2, Rgba(pixel)));
+
+            (
+                ImageHolder::new(image([0, 0, 255, 255]), "original.png"),
+                ImageHolder::new(image([255, 0, 0, 255]), "latest.png")
+                    .with_profile(Some(create_colour_profile(SWAPPED_ICC_PROFILE.to_vec()))),
+            )
+        }
+
+        #[test]
+        fn converts_images_with_other_profiles_to_srgb() {
+            let ((original, latest), profiles) =
+                handle_colour_profiles(create_swapped_pair(), ProfileHandling::Convert);
+
+            assert_eq!(
+                Some(ProfileResult {
+                    original: None,
+                    latest: Some("Swapped".to_string()),
+                    converted: true,
+                }),
+                profiles
+            );
+            assert_eq!(original.image.to_rgba8(), latest.image.to_rgba8());
+        }
+
+        #[test]
+        fn keeps_pixels_but_records_profiles_when_warning() {
+            let image_pair = create_swapped_pair();
+
+            let (handled_pair, profiles) =
+                handle_colour_profiles(image_pair.clone(), ProfileHandling::Warn);
+
+            assert_eq!(image_pair, handled_pair);
+            assert_eq!(Some("Swapped"), profiles.unwrap().latest.as_deref());
+        }
+
+        #[test]
+        fn leaves_images_with_an_srgb_profile_unconverted() {
+            let (original, latest) = create_swapped_pair();
+            let latest = ImageHolder::new(latest.image, "latest.png")
+                .with_profile(Some(create_colour_profile(SRGB_ICC_PROFILE.to_vec())));
+
+            let ((_, handled_latest), profiles) =
+                handle_colour_profiles((original, latest.clone()), ProfileHandling::Convert);
+
+            assert_eq!(latest, handled_latest);
+            assert!(!profiles.unwrap().converted);
+        }
+
+        #[test]
+        fn matches_images_without_a_profile_to_those_with_an_srgb_one() {
+            let (original, latest) = create_swapped_pair();
+            let srgb_latest = ImageHolder::new(latest.image.clone(), "latest.png")
+                .with_profile(Some(create_colour_profile(SRGB_ICC_PROFILE.to_vec())));
+
+            assert!(is_matching_profiles(&original, &srgb_latest));
+            assert!(!is_matching_profiles(&original, &latest));
+        }
+
+        #[test]
+        fn returns_no_profiles_when_ignoring() {
+            let (_, profiles) =
+                handle_colour_profiles(create_swapped_pair(), ProfileHandling::Ignore);
+
+            assert_eq!(None, profiles);
+        }
+    }
+}
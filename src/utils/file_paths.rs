@@ -1,17 +1,25 @@
+use std::{
+    collections::HashSet,
+    path::{self, Component, Path, PathBuf},
+};
+
 use crate::{
-    config::AppConfig,
+    config::CompareConfig,
     errors::{handling::create_image_not_paired_error, ivc::IVCError},
     logger::Logger,
+    models::DiffFormat,
 };
 
-use self::helpers::{are_file_path_pairs_valid, get_pairs_of_file_paths_for_images};
+use self::helpers::{
+    are_file_path_pairs_valid, get_pairing_key, get_pairs_of_file_paths_for_images,
+};
 
 pub fn get_file_path_pairs_if_valid(
-    config: &AppConfig,
+    config: &CompareConfig,
     original_paths: Vec<String>,
     latest_paths: Vec<String>,
 ) -> Result<Vec<(String, String)>, IVCError> {
-    let image_pairs = get_pairs_of_file_paths_for_images(original_paths, latest_paths);
+    let image_pairs = get_pairs_of_file_paths_for_images(config, original_paths, latest_paths);
 
     if are_file_path_pairs_valid(config, &image_pairs) {
         Logger::info(format!(
@@ -25,8 +33,395 @@ pub fn get_file_path_pairs_if_valid(
     Err(create_image_not_paired_error())
 }
 
+/// Returns the latest paths with no matching original, and the original paths with no matching
+/// latest, in that order.
+pub fn get_unpaired_file_paths(
+    config: &CompareConfig,
+    original_paths: &[String],
+    latest_paths: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let original_dir = config.get_original_images_dir();
+    let latest_dir = config.get_latest_images_dir();
+
+    let original_keys: HashSet<Option<String>> = original_paths
+        .iter()
+        .map(|location| get_pairing_key(config, location, &original_dir))
+        .collect();
+    let latest_keys: HashSet<Option<String>> = latest_paths
+        .iter()
+        .map(|location| get_pairing_key(config, location, &latest_dir))
+        .collect();
+
+    let new_paths = latest_paths
+        .iter()
+        .filter(|location| !original_keys.contains(&get_pairing_key(config, location, &latest_dir)))
+        .cloned()
+        .collect();
+    let missing_paths = original_paths
+        .iter()
+        .filter(|location| !latest_keys.contains(&get_pairing_key(config, location, &original_dir)))
+        .cloned()
+        .collect();
+
+    (new_paths, missing_paths)
+}
+
+/// Whether the image at the location, found within the root directory, is assigned to the shard
+/// being run, which is always the case when the run is not sharded.
+///
+/// The key used for pairing is what is hashed, so that an original and latest image that pair are
+/// always assigned to the same shard.
+pub fn is_in_shard(config: &CompareConfig, location: &str, root_dir: &str) -> bool {
+    match config.get_shard() {
+        Some(shard) => {
+            let key = get_pairing_key(config, location, root_dir).unwrap_or(location.to_owned());
+            shard.contains(&key)
+        }
+        None => true,
+    }
+}
+
+/// Returns the original that pairs with the latest location, if there is one.
+pub fn get_paired_original_location(
+    config: &CompareConfig,
+    latest_location: &str,
+    original_paths: &[String],
+) -> Option<String> {
+    let original_dir = config.get_original_images_dir();
+    let latest_key = get_pairing_key(config, latest_location, &config.get_latest_images_dir())?;
+
+    original_paths
+        .iter()
+        .find(|location| {
+            get_pairing_key(config, location, &original_dir).as_ref() == Some(&latest_key)
+        })
+        .cloned()
+}
+
+/// Returns a link to the location relative to the directory, such as for linking to images from
+/// a report written within that directory. Both are resolved against the current directory when
+/// relative.
+pub fn get_relative_link(from_dir: &str, to_location: &str) -> String {
+    let (from_path, to_path) = match (path::absolute(from_dir), path::absolute(to_location)) {
+        (Ok(from_path), Ok(to_path)) => (from_path, to_path),
+        _ => return to_location.to_string(),
+    };
+
+    let from_components: Vec<Component> = from_path.components().collect();
+    let to_components: Vec<Component> = to_path.components().collect();
+    let shared_count = from_components
+        .iter()
+        .zip(to_components.iter())
+        .take_while(|(from_component, to_component)| from_component == to_component)
+        .count();
+
+    let mut relative_link = PathBuf::new();
+    for _ in shared_count..from_components.len() {
+        relative_link.push(Component::ParentDir);
+    }
+    for component in to_components.iter().skip(shared_count) {
+        relative_link.push(component);
+    }
+
+    relative_link
+        .to_string_lossy()
+        .replace(std::path::MAIN_SEPARATOR, "/")
+}
+
+const MISMATCH_IMAGE_EXTENSION: &str = "png";
+const ANIMATED_MISMATCH_IMAGE_EXTENSION: &str = "gif";
+const PAGE_SEPARATOR: &str = "#page";
+const SVG_EXTENSION: &str = "svg";
+
+/// Returns the location a mismatch image should be written to, mirroring the latest image's
+/// location relative to the latest directory inside the mismatched directory.
+///
+/// Mismatch images are always written as png, whatever the format of the latest image. The
+/// mismatch image of a page of a multi-page image has the page in its name, such as
+/// `file.page3.png`.
+///
+/// When keeping the latest image's extension, it stays in the name, such as `file.bmp.png`, so
+/// that latest images differing only by extension, such as `file.png` and `file.bmp`, do not share
+/// a mismatch image.
+pub fn get_mismatched_image_location(
+    latest_location: &str,
+    latest_dir: &str,
+    mismatched_dir: &str,
+    is_keeping_extension: bool,
+) -> String {
+    let (latest_file_location, page) = split_page_location(latest_location);
+    let relative_location = get_relative_key(latest_file_location, latest_dir)
+        .unwrap_or(latest_file_location.to_string());
+    let extension = match page {
+        Some(page) => format!("page{page}.{MISMATCH_IMAGE_EXTENSION}"),
+        None => MISMATCH_IMAGE_EXTENSION.to_string(),
+    };
+    let location = Path::new(mismatched_dir).join(relative_location);
+
+    match is_keeping_extension {
+        true => format!("{}.{extension}", location.to_string_lossy()),
+        false => location
+            .with_extension(extension)
+            .to_string_lossy()
+            .to_string(),
+    }
+}
+
+/// Returns the location used for a single page of a multi-page image, such as `file.tiff#page3`,
+/// with pages counted from 1.
+pub fn get_page_location(location: &str, page: usize) -> String {
+    format!("{location}{PAGE_SEPARATOR}{page}")
+}
+
+/// Splits the location of a page of a multi-page image into the location of its file and the
+/// page, the page being `None` for any other location.
+pub fn split_page_location(location: &str) -> (&str, Option<usize>) {
+    match location.rsplit_once(PAGE_SEPARATOR) {
+        Some((file_location, page)) => match page.parse::<usize>() {
+            Ok(page) => (file_location, Some(page)),
+            Err(_) => (location, None),
+        },
+        None => (location, None),
+    }
+}
+
+/// Returns the location of the file holding the image, which is the location itself unless it
+/// is a page of a multi-page image.
+pub fn get_file_location(location: &str) -> &str {
+    split_page_location(location).0
+}
+
+/// Returns the location an animated mismatch image should be written to in place of the mismatch
+/// location, as animated mismatch images are always written as gif.
+pub fn get_animated_mismatched_image_location(mismatched_location: &str) -> String {
+    Path::new(mismatched_location)
+        .with_extension(ANIMATED_MISMATCH_IMAGE_EXTENSION)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Returns the location a mismatch image in the diff format should be written to in place of the
+/// mismatch location, such as `file.webp` in place of `file.png`.
+pub fn get_formatted_mismatched_image_location(
+    mismatched_location: &str,
+    format: DiffFormat,
+) -> String {
+    Path::new(mismatched_location)
+        .with_extension(format.get_extension())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Returns the location the blink gif of a pair should be written to alongside its mismatch
+/// location, such as `file.blink.gif` alongside `file.png`.
+pub fn get_blink_location(mismatched_location: &str) -> String {
+    Path::new(mismatched_location)
+        .with_extension(format!("blink.{ANIMATED_MISMATCH_IMAGE_EXTENSION}"))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Returns the location a crop of one of a pair's images, such as `original`, for its numbered
+/// region should be written to alongside its mismatch location, such as
+/// `file.region1.original.png`.
+pub fn get_region_crop_location(
+    mismatched_location: &str,
+    region_number: usize,
+    image_name: &str,
+    format: DiffFormat,
+) -> String {
+    Path::new(mismatched_location)
+        .with_extension(format!(
+            "region{region_number}.{image_name}.{}",
+            format.get_extension()
+        ))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Returns the location a rasterised svg image should be written to, alongside the mismatch
+/// location, such as `icon.original.png` for the original image of the pair.
+pub fn get_svg_raster_location(mismatched_location: &str, image_name: &str) -> String {
+    Path::new(mismatched_location)
+        .with_extension(format!("{image_name}.{MISMATCH_IMAGE_EXTENSION}"))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Whether the location is of an svg image, which is known by its extension, as svg images are
+/// rasterised rather than decoded.
+pub fn is_svg_location(location: &str) -> bool {
+    Path::new(location)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(SVG_EXTENSION))
+}
+
+/// Returns the location relative to the root directory it was found in, which is the key used to
+/// pair original and latest images with each other. Returns `None` when the location is not
+/// within the root.
+pub fn get_relative_key(location: &str, root_dir: &str) -> Option<String> {
+    Path::new(location)
+        .strip_prefix(root_dir)
+        .ok()
+        .map(|relative_location| relative_location.to_string_lossy().to_string())
+}
+
 #[cfg(test)]
 mod tests {
+    mod get_mismatched_image_location {
+        use crate::{
+            test_utils::constants::TestConstants, utils::file_paths::get_mismatched_image_location,
+        };
+
+        #[test]
+        fn returns_location_within_mismatched_directory_keeping_sub_directories() {
+            let latest_dir = format!("{}/{}", TestConstants::IMAGES, TestConstants::LATEST);
+            let mismatched_dir = format!("{}/mismatched", TestConstants::IMAGES);
+
+            let result = get_mismatched_image_location(
+                &format!("{latest_dir}/some_dir/some_image.png"),
+                &latest_dir,
+                &mismatched_dir,
+                false,
+            );
+
+            assert_eq!(format!("{mismatched_dir}/some_dir/some_image.png"), result);
+        }
+
+        #[test]
+        fn returns_png_location_whatever_the_latest_image_format() {
+            let latest_dir = format!("{}/{}", TestConstants::IMAGES, TestConstants::LATEST);
+            let mismatched_dir = format!("{}/mismatched", TestConstants::IMAGES);
+
+            let result = get_mismatched_image_location(
+                &format!("{latest_dir}/some_image.webp"),
+                &latest_dir,
+                &mismatched_dir,
+                false,
+            );
+
+            assert_eq!(format!("{mismatched_dir}/some_image.png"), result);
+        }
+
+        #[test]
+        fn returns_location_keeping_latest_extension_when_keeping_extension() {
+            let latest_dir = format!("{}/{}", TestConstants::IMAGES, TestConstants::LATEST);
+            let mismatched_dir = format!("{}/mismatched", TestConstants::IMAGES);
+
+            let results: Vec<String> = ["some_image.png", "some_image.bmp", "pages.tiff#page3"]
+                .iter()
+                .map(|name| {
+                    get_mismatched_image_location(
+                        &format!("{latest_dir}/{name}"),
+                        &latest_dir,
+                        &mismatched_dir,
+                        true,
+                    )
+                })
+                .collect();
+
+            assert_eq!(
+                vec![
+                    format!("{mismatched_dir}/some_image.png.png"),
+                    format!("{mismatched_dir}/some_image.bmp.png"),
+                    format!("{mismatched_dir}/pages.tiff.page3.png"),
+                ],
+                results
+            );
+        }
+
+        #[test]
+        fn returns_location_with_page_for_page_of_multi_page_image() {
+            let latest_dir = format!("{}/{}", TestConstants::IMAGES, TestConstants::LATEST);
+            let mismatched_dir = format!("{}/mismatched", TestConstants::IMAGES);
+
+            let result = get_mismatched_image_location(
+                &format!("{latest_dir}/some_image.tiff#page3"),
+                &latest_dir,
+                &mismatched_dir,
+                false,
+            );
+
+            assert_eq!(format!("{mismatched_dir}/some_image.page3.png"), result);
+        }
+    }
+
+    mod split_page_location {
+        use crate::utils::file_paths::{get_page_location, split_page_location};
+
+        #[test]
+        fn returns_file_location_and_page() {
+            let location = get_page_location("latest/some_image.tiff", 3);
+
+            assert_eq!("latest/some_image.tiff#page3", location);
+            assert_eq!(
+                ("latest/some_image.tiff", Some(3)),
+                split_page_location(&location)
+            );
+        }
+
+        #[test]
+        fn returns_location_without_page_when_not_a_page() {
+            for location in ["latest/some_image.tiff", "latest/#pages/some_image.tiff"] {
+                assert_eq!((location, None), split_page_location(location));
+            }
+        }
+    }
+
+    mod get_formatted_mismatched_image_location {
+        use crate::{
+            models::DiffFormat, utils::file_paths::get_formatted_mismatched_image_location,
+        };
+
+        #[test]
+        fn returns_location_with_the_extension_of_the_format() {
+            assert_eq!(
+                "mismatched/icons/cart.jpg",
+                get_formatted_mismatched_image_location(
+                    "mismatched/icons/cart.png",
+                    DiffFormat::Jpeg
+                )
+            );
+        }
+    }
+
+    mod get_blink_location {
+        use crate::utils::file_paths::get_blink_location;
+
+        #[test]
+        fn returns_gif_location_alongside_the_mismatch_location() {
+            assert_eq!(
+                "mismatched/icons/cart.blink.gif",
+                get_blink_location("mismatched/icons/cart.png")
+            );
+        }
+    }
+
+    mod get_region_crop_location {
+        use crate::{models::DiffFormat, utils::file_paths::get_region_crop_location};
+
+        #[test]
+        fn returns_location_named_after_the_region_and_image_alongside_the_mismatch_location() {
+            assert_eq!(
+                "mismatched/icons/cart.region2.diff.webp",
+                get_region_crop_location("mismatched/icons/cart.png", 2, "diff", DiffFormat::Webp)
+            );
+        }
+    }
+
+    mod get_svg_raster_location {
+        use crate::utils::file_paths::{get_svg_raster_location, is_svg_location};
+
+        #[test]
+        fn returns_png_location_named_after_the_image_alongside_the_mismatch_location() {
+            assert!(is_svg_location("latest/icons/cart.SVG"));
+
+            assert_eq!(
+                "mismatched/icons/cart.original.png",
+                get_svg_raster_location("mismatched/icons/cart.png", "original")
+            );
+        }
+    }
 
     mod get_file_path_pairs_if_valid {
 
@@ -141,16 +536,189 @@ mod tests {
         }
     }
 
+    mod get_file_path_pairs_if_valid_with_independent_roots {
+        use crate::{
+            config::CompareConfig, test_utils::files::create_file_names,
+            utils::file_paths::get_file_path_pairs_if_valid,
+        };
+
+        #[test]
+        fn returns_pairs_when_relative_paths_match_against_each_root() {
+            let config = CompareConfig::builder()
+                .original_dir("/baselines/app")
+                .latest_dir("captures")
+                .build();
+            let original_file_names = create_file_names("/baselines/app");
+            let latest_file_names = create_file_names("captures");
+
+            let expected = get_file_path_pairs_if_valid(
+                &config,
+                original_file_names.to_owned(),
+                latest_file_names.to_owned(),
+            );
+
+            assert_eq!(
+                original_file_names
+                    .into_iter()
+                    .zip(latest_file_names)
+                    .collect::<Vec<(String, String)>>(),
+                expected.unwrap()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_a_path_is_outside_its_root() {
+            let config = CompareConfig::builder()
+                .original_dir("/baselines/app")
+                .latest_dir("captures")
+                .build();
+            let original_file_names = create_file_names("/baselines/other");
+            let latest_file_names = create_file_names("captures");
+
+            let expected =
+                get_file_path_pairs_if_valid(&config, original_file_names, latest_file_names);
+
+            assert!(expected.is_err());
+        }
+    }
+
+    mod get_file_path_pairs_if_valid_across_formats {
+        use crate::{config::CompareConfig, utils::file_paths::get_file_path_pairs_if_valid};
+
+        fn create_config(match_across_formats: bool) -> CompareConfig {
+            CompareConfig::builder()
+                .directory("images")
+                .match_across_formats(match_across_formats)
+                .build()
+        }
+
+        fn create_paths(base: &str, ext: &str) -> Vec<String> {
+            vec![format!("{base}/a.{ext}"), format!("{base}/a.q.{ext}")]
+        }
+
+        #[test]
+        fn returns_pairs_matched_by_name_when_extensions_differ_and_requested() {
+            let expected = get_file_path_pairs_if_valid(
+                &create_config(true),
+                create_paths("images/original", "png"),
+                create_paths("images/latest", "webp"),
+            );
+
+            assert_eq!(
+                vec![
+                    (
+                        "images/original/a.png".to_string(),
+                        "images/latest/a.webp".to_string()
+                    ),
+                    (
+                        "images/original/a.q.png".to_string(),
+                        "images/latest/a.q.webp".to_string()
+                    ),
+                ],
+                expected.unwrap()
+            );
+        }
+
+        #[test]
+        fn returns_error_when_extensions_differ_and_not_requested() {
+            let expected = get_file_path_pairs_if_valid(
+                &create_config(false),
+                create_paths("images/original", "png"),
+                create_paths("images/latest", "webp"),
+            );
+
+            assert!(expected.is_err());
+        }
+    }
+
+    mod get_relative_link {
+        use crate::utils::file_paths::get_relative_link;
+
+        #[test]
+        fn returns_link_from_directory_to_sibling_directory() {
+            assert_eq!(
+                "../latest/some_dir/image.png",
+                get_relative_link("images/mismatched", "images/latest/some_dir/image.png")
+            );
+        }
+
+        #[test]
+        fn returns_link_to_location_within_directory() {
+            assert_eq!(
+                "some_dir/image.png",
+                get_relative_link(
+                    "/images/mismatched",
+                    "/images/mismatched/some_dir/image.png"
+                )
+            );
+        }
+    }
+
+    mod get_paired_original_location {
+        use crate::{
+            test_utils::{config::create_config_for_test, constants::TestConstants},
+            utils::file_paths::get_paired_original_location,
+        };
+
+        #[test]
+        fn returns_original_with_matching_relative_path() {
+            let config = create_config_for_test(TestConstants::IMAGES);
+            let original_paths = vec![
+                "images/original/a.png".to_string(),
+                "images/original/nested/b.png".to_string(),
+            ];
+
+            assert_eq!(
+                Some("images/original/nested/b.png".to_string()),
+                get_paired_original_location(
+                    &config,
+                    "images/latest/nested/b.png",
+                    &original_paths
+                )
+            );
+            assert_eq!(
+                None,
+                get_paired_original_location(&config, "images/latest/c.png", &original_paths)
+            );
+        }
+    }
+
+    mod get_unpaired_file_paths {
+        use crate::{
+            test_utils::{config::create_config_for_test, constants::TestConstants},
+            utils::file_paths::get_unpaired_file_paths,
+        };
+
+        #[test]
+        fn returns_new_latest_paths_and_missing_original_paths() {
+            let config = create_config_for_test(TestConstants::IMAGES);
+            let original_paths = vec![
+                "images/original/kept.png".to_string(),
+                "images/original/gone.png".to_string(),
+            ];
+            let latest_paths = vec![
+                "images/latest/kept.png".to_string(),
+                "images/latest/new.png".to_string(),
+            ];
+
+            let (new_paths, missing_paths) =
+                get_unpaired_file_paths(&config, &original_paths, &latest_paths);
+
+            assert_eq!(vec!["images/latest/new.png".to_string()], new_paths);
+            assert_eq!(vec!["images/original/gone.png".to_string()], missing_paths);
+        }
+    }
+
     mod test_helpers {
         use crate::{
-            config::AppConfig,
+            config::CompareConfig,
             test_utils::{
                 config::create_config_for_test, constants::TestConstants, files::create_file_names,
             },
         };
 
         pub fn setup() -> (
-            AppConfig,
+            CompareConfig,
             Vec<std::string::String>,
             Vec<std::string::String>,
         ) {
@@ -173,19 +741,45 @@ mod tests {
 }
 
 mod helpers {
-    use crate::{config::AppConfig, logger::Logger};
+    use std::path::Path;
 
-    pub fn are_file_path_pairs_valid(config: &AppConfig, image_pairs: &[(String, String)]) -> bool {
-        for (orig_image_location, lat_image_location) in image_pairs.iter() {
-            let original_clean = orig_image_location
-                .strip_prefix(&config.get_original_images_dir())
-                .unwrap();
+    use crate::{config::CompareConfig, logger::Logger};
+
+    use super::get_relative_key;
 
-            let latest_clean = lat_image_location
-                .strip_prefix(&config.get_latest_images_dir())
-                .unwrap();
+    pub fn get_pairing_key(
+        config: &CompareConfig,
+        location: &str,
+        root_dir: &str,
+    ) -> Option<String> {
+        let relative_key = get_relative_key(location, root_dir)?;
 
-            if original_clean != latest_clean {
+        if !config.is_matching_across_formats() {
+            return Some(relative_key);
+        }
+
+        Some(
+            Path::new(&relative_key)
+                .with_extension("")
+                .to_string_lossy()
+                .to_string(),
+        )
+    }
+
+    pub fn are_file_path_pairs_valid(
+        config: &CompareConfig,
+        image_pairs: &[(String, String)],
+    ) -> bool {
+        let original_dir = config.get_original_images_dir();
+        let latest_dir = config.get_latest_images_dir();
+
+        for (orig_image_location, lat_image_location) in image_pairs.iter() {
+            let original_key = get_pairing_key(config, orig_image_location, &original_dir);
+            let latest_key = get_pairing_key(config, lat_image_location, &latest_dir);
+
+            if original_key.is_none() || original_key != latest_key {
+                let original_clean = original_key.unwrap_or(orig_image_location.to_owned());
+                let latest_clean = latest_key.unwrap_or(lat_image_location.to_owned());
                 Logger::error(format!(
                     "file paths should match but don't. Original: '{original_clean}', Latest: '{latest_clean}'"
                 ));
@@ -196,10 +790,19 @@ mod helpers {
         true
     }
 
+    /// Pairs the paths in order of their pairing key, so that paths which only match once their
+    /// extension is ignored still line up with each other.
     pub fn get_pairs_of_file_paths_for_images(
-        orig: Vec<String>,
-        latest: Vec<String>,
+        config: &CompareConfig,
+        mut orig: Vec<String>,
+        mut latest: Vec<String>,
     ) -> Vec<(String, String)> {
+        let original_dir = config.get_original_images_dir();
+        let latest_dir = config.get_latest_images_dir();
+
+        orig.sort_by_cached_key(|location| get_pairing_key(config, location, &original_dir));
+        latest.sort_by_cached_key(|location| get_pairing_key(config, location, &latest_dir));
+
         orig.iter()
             .zip(latest.iter())
             .map(|(orig_elem, latest_elem)| (orig_elem.to_owned(), latest_elem.to_owned()))
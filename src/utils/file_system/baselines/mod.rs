@@ -0,0 +1,74 @@
+use std::fs;
+
+use image::ImageError;
+
+use crate::errors::{handling::create_io_write_error, ivc::IVCError};
+
+use super::images::create_parent_directories;
+
+/// Copies the file to the location, creating any missing parent directories and replacing any
+/// file already at the location.
+pub fn copy_file_to_location(from_location: &str, to_location: &str) -> Result<(), IVCError> {
+    create_parent_directories(to_location)?;
+
+    match fs::copy(from_location, to_location) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(create_io_write_error(
+            to_location.to_owned(),
+            ImageError::IoError(error),
+        )),
+    }
+}
+
+pub fn remove_file_at_location(location: &str) -> Result<(), IVCError> {
+    fs::remove_file(location)
+        .map_err(|error| create_io_write_error(location.to_owned(), ImageError::IoError(error)))
+}
+
+#[cfg(test)]
+mod tests {
+    mod copy_file_to_location {
+        use std::fs;
+
+        use crate::{
+            test_utils::files::create_temp_dir_handler,
+            utils::file_system::baselines::copy_file_to_location,
+        };
+
+        #[test]
+        fn replaces_existing_file_and_creates_missing_directories() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let from_location = temp_dir_handler.get_location_of_file_name("from.png");
+            let existing_location = temp_dir_handler.get_location_of_file_name("existing.png");
+            let nested_location = temp_dir_handler.get_location_of_file_name("nested/to.png");
+            fs::write(&from_location, "latest").unwrap();
+            fs::write(&existing_location, "original").unwrap();
+
+            copy_file_to_location(&from_location, &existing_location).unwrap();
+            copy_file_to_location(&from_location, &nested_location).unwrap();
+
+            assert_eq!("latest", fs::read_to_string(existing_location).unwrap());
+            assert_eq!("latest", fs::read_to_string(nested_location).unwrap());
+        }
+    }
+
+    mod remove_file_at_location {
+        use crate::{
+            test_utils::files::create_temp_dir_handler,
+            utils::file_system::baselines::remove_file_at_location,
+        };
+
+        #[test]
+        fn returns_error_when_file_does_not_exist() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let location = temp_dir_handler.get_location_of_file_name("missing.png");
+
+            let result = remove_file_at_location(&location);
+
+            assert_eq!(
+                format!("Issue writing file at location: '{location}'. Message: 'No such file or directory (os error 2)'"),
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+}
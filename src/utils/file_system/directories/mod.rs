@@ -1,12 +1,17 @@
-use crate::{config::AppConfig, errors::ivc::IVCError, logger::Logger};
+use crate::{config::CompareConfig, errors::ivc::IVCError, logger::Logger};
 
-use self::helpers::validate_directories_exist;
+use self::helpers::{validate_directories_do_not_overlap, validate_directories_exist};
 
-pub fn get_directories_if_exist(config: &AppConfig) -> Result<(String, String), IVCError> {
+/// Returns the original and latest directories, as each is an independent root they are also
+/// checked to not be nested within each other or the mismatched directory, as otherwise images
+/// would be picked up from the wrong root.
+pub fn get_directories_if_exist(config: &CompareConfig) -> Result<(String, String), IVCError> {
     let original_dir = config.get_original_images_dir();
     let latest_dir = config.get_latest_images_dir();
+    let mismatched_dir = config.get_mismatched_images_dir();
 
     validate_directories_exist(&original_dir, &latest_dir)?;
+    validate_directories_do_not_overlap(&[&original_dir, &latest_dir, &mismatched_dir])?;
 
     Logger::info("Found directories for original and latest images".to_string());
     Logger::debug(format!(
@@ -20,7 +25,7 @@ pub fn get_directories_if_exist(config: &AppConfig) -> Result<(String, String),
 mod tests {
     mod get_directories_if_exist {
         use crate::{
-            config::AppConfig,
+            config::CompareConfig,
             test_utils::{
                 config::create_config_for_test,
                 constants::TestConstants,
@@ -31,7 +36,7 @@ mod tests {
         fn setup(
             has_original: bool,
             has_latest: bool,
-        ) -> (TempDirHandler, String, String, AppConfig) {
+        ) -> (TempDirHandler, String, String, CompareConfig) {
             let temp_dir_handler = create_temp_dir_handler();
             let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
 
@@ -102,9 +107,43 @@ mod tests {
             }
         }
 
+        mod returns_overlapping_error {
+            use crate::{
+                config::CompareConfig,
+                test_utils::{constants::TestConstants, files::create_temp_dir_handler},
+                utils::file_system::directories::get_directories_if_exist,
+            };
+
+            #[test]
+            fn when_output_directory_is_within_latest_directory() {
+                let temp_dir_handler = create_temp_dir_handler();
+                temp_dir_handler.create_dir_in_temp_dir(TestConstants::ORIGINAL);
+                temp_dir_handler.create_dir_in_temp_dir(TestConstants::LATEST);
+                let latest_dir = temp_dir_handler.get_location_of_file_name(TestConstants::LATEST);
+                let output_dir = format!("{latest_dir}/diffs");
+
+                let config = CompareConfig::builder()
+                    .directory(temp_dir_handler.get_temp_dir_path())
+                    .output_dir(&output_dir)
+                    .build();
+
+                let expected_error = get_directories_if_exist(&config);
+
+                assert!(expected_error.is_err());
+                assert_eq!(
+                    format!("Directories must not be nested within each other: '{latest_dir}' and '{output_dir}'."),
+                    expected_error.unwrap_err().to_string()
+                );
+            }
+        }
+
         mod returns_directories {
-            use crate::utils::file_system::directories::{
-                get_directories_if_exist, tests::get_directories_if_exist::setup,
+            use crate::{
+                config::CompareConfig,
+                test_utils::files::create_temp_dir_handler,
+                utils::file_system::directories::{
+                    get_directories_if_exist, tests::get_directories_if_exist::setup,
+                },
             };
 
             #[test]
@@ -115,14 +154,35 @@ mod tests {
                 assert_eq!(original_dir, actual_original);
                 assert_eq!(latest_dir, actual_latest);
             }
+
+            #[test]
+            fn when_independent_original_and_latest_roots_exist() {
+                let original_handler = create_temp_dir_handler();
+                let latest_handler = create_temp_dir_handler();
+
+                let config = CompareConfig::builder()
+                    .directory(original_handler.get_temp_dir_path())
+                    .original_dir(original_handler.get_temp_dir_path())
+                    .latest_dir(latest_handler.get_temp_dir_path())
+                    .output_dir(latest_handler.get_location_of_file_name("../ivc-diffs"))
+                    .build();
+
+                let (actual_original, actual_latest) = get_directories_if_exist(&config).unwrap();
+
+                assert_eq!(original_handler.get_temp_dir_path(), actual_original);
+                assert_eq!(latest_handler.get_temp_dir_path(), actual_latest);
+            }
         }
     }
 }
 
 mod helpers {
-    use std::path::Path;
+    use std::path::{Component, Path, PathBuf};
 
-    use crate::errors::{handling::create_missing_directories_error, ivc::IVCError};
+    use crate::errors::{
+        handling::{create_missing_directories_error, create_overlapping_directories_error},
+        ivc::IVCError,
+    };
 
     fn directory_exists(dir: &str) -> bool {
         if Path::new(&dir).is_dir() {
@@ -150,4 +210,37 @@ mod helpers {
             latest_dir_exists,
         ))
     }
+
+    fn normalise_path(dir: &str) -> PathBuf {
+        let mut normalised = PathBuf::new();
+
+        for component in Path::new(dir).components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir if normalised.file_name().is_some() => {
+                    normalised.pop();
+                }
+                component => normalised.push(component),
+            }
+        }
+
+        normalised
+    }
+
+    pub fn validate_directories_do_not_overlap(directories: &[&String]) -> Result<(), IVCError> {
+        for (index, dir_one) in directories.iter().enumerate() {
+            for dir_two in directories.iter().skip(index + 1) {
+                let (path_one, path_two) = (normalise_path(dir_one), normalise_path(dir_two));
+
+                if path_one.starts_with(&path_two) || path_two.starts_with(&path_one) {
+                    return Err(create_overlapping_directories_error(
+                        dir_one.to_string(),
+                        dir_two.to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,18 +1,21 @@
+use std::collections::HashSet;
+
 use crate::{
-    config::AppConfig,
+    config::CompareConfig,
     errors::{handling::create_image_count_mismatch_error, ivc::IVCError},
     logger::Logger,
+    utils::file_paths::is_in_shard,
 };
 
-use self::helpers::find_files;
+use self::helpers::{find_all_files, find_files, has_extension_alias, is_desired_extension};
 
 pub fn get_files_if_directories_match_in_file_count(
-    config: &AppConfig,
+    config: &CompareConfig,
     original_dir: String,
     latest_dir: String,
 ) -> Result<(Vec<std::string::String>, Vec<std::string::String>), IVCError> {
-    let orig_image_file_paths = find_files(&original_dir, &config.image_extension);
-    let latest_images_file_paths = find_files(&latest_dir, &config.image_extension);
+    let (orig_image_file_paths, latest_images_file_paths) =
+        get_files_for_directories(config, &original_dir, &latest_dir);
 
     if orig_image_file_paths.len() == latest_images_file_paths.len() {
         Logger::info("Retrieved file paths for images".to_string());
@@ -29,6 +32,70 @@ pub fn get_files_if_directories_match_in_file_count(
     ))
 }
 
+/// Whether images with more than one extension could be found for the desired extensions, such as
+/// 'jpg' finding both '.jpg' and '.jpeg' images.
+pub fn is_finding_several_extensions(desired_exts: &[String]) -> bool {
+    desired_exts.len() > 1
+        || desired_exts
+            .iter()
+            .any(|desired_ext| has_extension_alias(desired_ext))
+}
+
+/// Returns the sorted paths of every image file, with one of the configured extensions, within
+/// the original and latest directories, leaving out any that are excluded by the config's globs or
+/// assigned to another shard.
+pub fn get_files_for_directories(
+    config: &CompareConfig,
+    original_dir: &str,
+    latest_dir: &str,
+) -> (Vec<std::string::String>, Vec<std::string::String>) {
+    let find_compared_files = |dir: &str| -> Vec<String> {
+        find_files(dir, config.get_image_extensions())
+            .into_iter()
+            .filter(|location| !config.is_excluded(location, dir))
+            .filter(|location| is_in_shard(config, location, dir))
+            .collect()
+    };
+
+    (
+        find_compared_files(original_dir),
+        find_compared_files(latest_dir),
+    )
+}
+
+/// Returns the sorted paths of every file within the original and latest directories that
+/// `get_files_for_directories` leaves out, so would not be compared.
+pub fn get_filtered_files_for_directories(
+    config: &CompareConfig,
+    original_dir: &str,
+    latest_dir: &str,
+) -> Vec<String> {
+    let (orig_image_file_paths, latest_images_file_paths) =
+        get_files_for_directories(config, original_dir, latest_dir);
+    let kept_file_paths: HashSet<String> = orig_image_file_paths
+        .into_iter()
+        .chain(latest_images_file_paths)
+        .collect();
+
+    let mut filtered_files: Vec<String> = find_all_files(original_dir)
+        .into_iter()
+        .chain(find_all_files(latest_dir))
+        .filter(|location| !kept_file_paths.contains(location))
+        .collect();
+
+    filtered_files.sort();
+
+    filtered_files
+}
+
+/// Whether the location has one of the configured image extensions.
+pub fn is_image_file_location(config: &CompareConfig, location: &str) -> bool {
+    std::path::Path::new(location)
+        .extension()
+        .map(|ext| is_desired_extension(ext, config.get_image_extensions()))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     mod get_files_if_directories_match_in_file_count {
@@ -192,6 +259,179 @@ mod tests {
         }
     }
 
+    mod get_files_if_directories_match_in_file_count_for_multiple_formats {
+        use std::fs::File;
+
+        use crate::{
+            config::CompareConfig,
+            test_utils::{constants::TestConstants, files::create_temp_dir_handler},
+            utils::file_system::files::get_files_if_directories_match_in_file_count,
+        };
+
+        #[test]
+        fn returns_files_for_every_format_and_its_alias() {
+            let temp_dir_handler = create_temp_dir_handler();
+            temp_dir_handler.create_dir_in_temp_dir(TestConstants::ORIGINAL);
+            temp_dir_handler.create_dir_in_temp_dir(TestConstants::LATEST);
+            for file_name in ["a.png", "b.JPEG", "c.webp", "d.gif"] {
+                for dir in [TestConstants::ORIGINAL, TestConstants::LATEST] {
+                    File::create(
+                        temp_dir_handler.get_location_of_file_name(&format!("{dir}/{file_name}")),
+                    )
+                    .unwrap();
+                }
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .image_extensions(["png", "jpg", "webp"])
+                .build();
+
+            let (original, latest) = get_files_if_directories_match_in_file_count(
+                &config,
+                config.get_original_images_dir(),
+                config.get_latest_images_dir(),
+            )
+            .unwrap();
+
+            let expected_original: Vec<String> = ["a.png", "b.JPEG", "c.webp"]
+                .map(|file_name| format!("{}/{file_name}", config.get_original_images_dir()))
+                .to_vec();
+
+            assert_eq!(expected_original, original);
+            assert_eq!(3, latest.len());
+        }
+    }
+
+    mod get_files_for_directories {
+        use std::fs::{self, File};
+
+        use crate::{
+            config::{CompareConfig, PathOverride},
+            test_utils::{constants::TestConstants, files::create_temp_dir_handler},
+            utils::file_system::files::get_files_for_directories,
+        };
+
+        #[test]
+        fn leaves_out_ignored_files_from_both_directories() {
+            let temp_dir_handler = create_temp_dir_handler();
+            for dir in [TestConstants::ORIGINAL, TestConstants::LATEST] {
+                fs::create_dir_all(
+                    temp_dir_handler.get_location_of_file_name(&format!("{dir}/wip")),
+                )
+                .unwrap();
+                for file_name in ["a.png", "b.tmp.png", "wip/c.png"] {
+                    File::create(
+                        temp_dir_handler.get_location_of_file_name(&format!("{dir}/{file_name}")),
+                    )
+                    .unwrap();
+                }
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .exclude(["*.tmp.png"])
+                .path_override(PathOverride {
+                    paths: vec!["wip/**".to_string()],
+                    ignore: true,
+                    ..PathOverride::default()
+                })
+                .build();
+
+            let (original, latest) = get_files_for_directories(
+                &config,
+                &config.get_original_images_dir(),
+                &config.get_latest_images_dir(),
+            );
+
+            assert_eq!(
+                vec![format!("{}/a.png", config.get_original_images_dir())],
+                original
+            );
+            assert_eq!(
+                vec![format!("{}/a.png", config.get_latest_images_dir())],
+                latest
+            );
+        }
+    }
+
+    mod get_filtered_files_for_directories {
+        use std::fs::{self, File};
+
+        use crate::{
+            config::CompareConfig,
+            test_utils::{constants::TestConstants, files::create_temp_dir_handler},
+            utils::file_system::files::get_filtered_files_for_directories,
+        };
+
+        #[test]
+        fn returns_files_left_out_by_extension_or_glob() {
+            let temp_dir_handler = create_temp_dir_handler();
+            for dir in [TestConstants::ORIGINAL, TestConstants::LATEST] {
+                fs::create_dir_all(temp_dir_handler.get_location_of_file_name(dir)).unwrap();
+                for file_name in ["a.png", "b.txt", "c.wip.png"] {
+                    File::create(
+                        temp_dir_handler.get_location_of_file_name(&format!("{dir}/{file_name}")),
+                    )
+                    .unwrap();
+                }
+            }
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .exclude(["*.wip.png"])
+                .build();
+
+            let filtered_files = get_filtered_files_for_directories(
+                &config,
+                &config.get_original_images_dir(),
+                &config.get_latest_images_dir(),
+            );
+
+            let latest_dir = config.get_latest_images_dir();
+            let original_dir = config.get_original_images_dir();
+            assert_eq!(
+                vec![
+                    format!("{latest_dir}/b.txt"),
+                    format!("{latest_dir}/c.wip.png"),
+                    format!("{original_dir}/b.txt"),
+                    format!("{original_dir}/c.wip.png"),
+                ],
+                filtered_files
+            );
+        }
+    }
+
+    mod get_files_if_directories_match_in_file_count_with_filters {
+        use crate::{
+            config::CompareConfig,
+            test_utils::{constants::TestConstants, files::create_temp_dir_handler},
+            utils::file_system::files::{
+                get_files_if_directories_match_in_file_count,
+                tests::test_helpers::create_dirs_and_files,
+            },
+        };
+
+        #[test]
+        fn returns_files_when_only_filtered_out_files_differ_in_count() {
+            let temp_dir_handler = create_temp_dir_handler();
+            create_dirs_and_files(&temp_dir_handler, TestConstants::ORIGINAL, "first", false);
+            create_dirs_and_files(&temp_dir_handler, TestConstants::LATEST, "first", true);
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .include(["common/**", "first/**"])
+                .exclude(["**/find_six.png"])
+                .build();
+
+            let (original, latest) = get_files_if_directories_match_in_file_count(
+                &config,
+                config.get_original_images_dir(),
+                config.get_latest_images_dir(),
+            )
+            .unwrap();
+
+            assert_eq!(4, original.len());
+            assert_eq!(4, latest.len());
+        }
+    }
+
     mod test_helpers {
         use std::{
             fs::{create_dir_all, File},
@@ -267,20 +507,50 @@ mod tests {
 }
 
 mod helpers {
+    use std::{ffi::OsStr, path::Path};
+
     use walkdir::WalkDir;
 
-    pub fn find_files(dir: &str, desired_ext: &str) -> Vec<String> {
-        let mut files: Vec<String> = WalkDir::new(dir)
+    const EXTENSION_ALIASES: [(&str, &str); 2] = [("jpg", "jpeg"), ("tif", "tiff")];
+
+    pub fn is_desired_extension(ext: &OsStr, desired_exts: &[String]) -> bool {
+        let ext = ext.to_string_lossy().to_lowercase();
+
+        desired_exts.iter().any(|desired_ext| {
+            let desired_ext = desired_ext.to_lowercase();
+            ext == desired_ext
+                || EXTENSION_ALIASES.iter().any(|(alias_one, alias_two)| {
+                    (ext == *alias_one && desired_ext == *alias_two)
+                        || (ext == *alias_two && desired_ext == *alias_one)
+                })
+        })
+    }
+
+    pub fn has_extension_alias(desired_ext: &str) -> bool {
+        let desired_ext = desired_ext.to_lowercase();
+
+        EXTENSION_ALIASES
+            .iter()
+            .any(|(alias_one, alias_two)| desired_ext == *alias_one || desired_ext == *alias_two)
+    }
+
+    pub fn find_files(dir: &str, desired_exts: &[String]) -> Vec<String> {
+        find_all_files(dir)
             .into_iter()
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| entry.file_type().is_file())
-            .filter(|entry| {
-                entry
-                    .path()
+            .filter(|location| {
+                Path::new(location)
                     .extension()
-                    .map(|ext| ext == desired_ext)
+                    .map(|ext| is_desired_extension(ext, desired_exts))
                     .unwrap_or(false)
             })
+            .collect()
+    }
+
+    pub fn find_all_files(dir: &str) -> Vec<String> {
+        let mut files: Vec<String> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
             .map(|entry| entry.path().to_string_lossy().to_string())
             .collect();
 
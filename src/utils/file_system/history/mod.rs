@@ -0,0 +1,215 @@
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    errors::{handling::create_snapshot_not_found_error, ivc::IVCError},
+    models::{SnapshotEntry, SnapshotManifest},
+    utils::file_paths::get_relative_key,
+};
+
+use super::{
+    baselines::{copy_file_to_location, remove_file_at_location},
+    json::{read_json_file, write_json_file},
+};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Archives the current version of each original into a new timestamped snapshot within the
+/// history directory, recording originals that do not exist yet so a rollback removes them.
+pub fn create_snapshot(
+    history_dir: &str,
+    original_dir: &str,
+    originals: &[String],
+) -> Result<SnapshotManifest, IVCError> {
+    let created_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+
+    let mut id_ms = created_at_ms;
+    while Path::new(history_dir).join(id_ms.to_string()).exists() {
+        id_ms += 1;
+    }
+    let id = id_ms.to_string();
+    let snapshot_dir = Path::new(history_dir).join(&id);
+
+    let mut entries: Vec<SnapshotEntry> = Vec::new();
+
+    for original in originals.iter() {
+        if entries.iter().any(|entry| &entry.original == original) {
+            continue;
+        }
+
+        let backup = match Path::new(original).is_file() {
+            true => {
+                let relative_key = get_relative_key(original, original_dir).unwrap_or(format!(
+                    "{}-{}",
+                    entries.len(),
+                    get_file_name(original)
+                ));
+                let backup = snapshot_dir
+                    .join(relative_key)
+                    .to_string_lossy()
+                    .to_string();
+                copy_file_to_location(original, &backup)?;
+                Some(backup)
+            }
+            false => None,
+        };
+
+        entries.push(SnapshotEntry {
+            original: original.to_owned(),
+            backup,
+        });
+    }
+
+    let manifest = SnapshotManifest {
+        id,
+        created_at_ms,
+        entries,
+    };
+
+    write_json_file(
+        &snapshot_dir.join(MANIFEST_FILE).to_string_lossy(),
+        &manifest,
+    )?;
+
+    Ok(manifest)
+}
+
+/// Returns the ids of every snapshot in the history directory, oldest first.
+pub fn get_snapshot_ids(history_dir: &str) -> Vec<String> {
+    let mut ids: Vec<String> = match fs::read_dir(history_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join(MANIFEST_FILE).is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    ids.sort_by_key(|id| (id.len(), id.to_owned()));
+
+    ids
+}
+
+pub fn read_snapshot_manifest(history_dir: &str, id: &str) -> Result<SnapshotManifest, IVCError> {
+    let location = Path::new(history_dir).join(id).join(MANIFEST_FILE);
+
+    if !location.is_file() {
+        return Err(create_snapshot_not_found_error(id));
+    }
+
+    read_json_file(&location.to_string_lossy())
+}
+
+/// Puts every original back to how it was when the snapshot was taken.
+pub fn restore_snapshot(manifest: &SnapshotManifest) -> Result<(), IVCError> {
+    for entry in manifest.entries.iter() {
+        match &entry.backup {
+            Some(backup) => copy_file_to_location(backup, &entry.original)?,
+            None if Path::new(&entry.original).exists() => {
+                remove_file_at_location(&entry.original)?
+            }
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn get_file_name(location: &str) -> String {
+    Path::new(location)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_snapshot {
+        use std::fs;
+
+        use crate::{
+            test_utils::files::create_temp_dir_handler,
+            utils::file_system::history::{
+                create_snapshot, get_snapshot_ids, read_snapshot_manifest, restore_snapshot,
+            },
+        };
+
+        #[test]
+        fn archives_existing_originals_and_restores_them() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let location = |name: &str| temp_dir_handler.get_location_of_file_name(name);
+            let (history_dir, original_dir) = (location("history"), location("original"));
+            fs::create_dir_all(location("original/nested")).unwrap();
+            fs::write(location("original/nested/a.png"), "before").unwrap();
+
+            let manifest = create_snapshot(
+                &history_dir,
+                &original_dir,
+                &[
+                    location("original/nested/a.png"),
+                    location("original/new.png"),
+                ],
+            )
+            .unwrap();
+
+            assert_eq!(
+                Some(format!("{history_dir}/{}/nested/a.png", manifest.id)),
+                manifest.entries[0].backup
+            );
+            assert_eq!(None, manifest.entries[1].backup);
+            assert_eq!(vec![manifest.id.to_owned()], get_snapshot_ids(&history_dir));
+            assert_eq!(
+                manifest,
+                read_snapshot_manifest(&history_dir, &manifest.id).unwrap()
+            );
+
+            fs::write(location("original/nested/a.png"), "after").unwrap();
+            fs::write(location("original/new.png"), "after").unwrap();
+
+            restore_snapshot(&manifest).unwrap();
+
+            assert_eq!(
+                "before",
+                fs::read_to_string(location("original/nested/a.png")).unwrap()
+            );
+            assert!(!std::path::Path::new(&location("original/new.png")).exists());
+        }
+
+        #[test]
+        fn uses_unique_ids_for_snapshots_taken_at_the_same_time() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let history_dir = temp_dir_handler.get_location_of_file_name("history");
+
+            let first = create_snapshot(&history_dir, "original", &[]).unwrap();
+            let second = create_snapshot(&history_dir, "original", &[]).unwrap();
+
+            assert_ne!(first.id, second.id);
+            assert_eq!(vec![first.id, second.id], get_snapshot_ids(&history_dir));
+        }
+    }
+
+    mod read_snapshot_manifest {
+        use crate::{
+            test_utils::files::create_temp_dir_handler,
+            utils::file_system::history::read_snapshot_manifest,
+        };
+
+        #[test]
+        fn returns_error_when_snapshot_does_not_exist() {
+            let temp_dir_handler = create_temp_dir_handler();
+
+            let result = read_snapshot_manifest(temp_dir_handler.get_temp_dir_path(), "123");
+
+            assert_eq!(
+                "Could not find baseline snapshot: '123'.",
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+}
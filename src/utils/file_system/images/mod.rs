@@ -1,24 +1,407 @@
-use crate::{errors::ivc::IVCError, models::ImageHolder};
-
-use self::helpers::get_image_from_file_location;
-
+use std::{fs::File, io::BufWriter};
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    error::{EncodingError, ImageFormatHint},
+    Delay, DynamicImage, Frame, ImageError, ImageFormat,
+};
+use image_webp::{ColorType, WebPEncoder};
+
+use crate::{
+    errors::{
+        handling::{create_io_write_error, create_page_count_mismatch_error},
+        ivc::IVCError,
+    },
+    models::{AnimationFrame, ImageHolder, SvgOptions},
+    utils::file_paths::{get_svg_raster_location, is_svg_location},
+};
+
+pub use self::helpers::create_parent_directories;
+use self::helpers::{get_image_from_file_location, get_pages_from_file_location};
+
+/// Reads both images, rasterising any svg image with the svg options.
 pub fn get_pair_of_images_from_file_locations(
     image_location_one: &str,
     image_location_two: &str,
+    svg_options: &SvgOptions,
 ) -> Result<(ImageHolder, ImageHolder), IVCError> {
-    let image_one = get_image_from_file_location(image_location_one)?;
-    let image_two = get_image_from_file_location(image_location_two)?;
+    let image_one = get_image_from_file_location(image_location_one, svg_options)?;
+    let image_two = get_image_from_file_location(image_location_two, svg_options)?;
 
     Ok((image_one, image_two))
 }
 
+/// Reads both images, pairing up their pages when they are multi-page tiffs, each page's image
+/// having the location of its page, such as `file.tiff#page3`. Any other pair is returned alone,
+/// with any svg image rasterised with the svg options.
+pub fn get_pairs_of_pages_from_file_locations(
+    image_location_one: &str,
+    image_location_two: &str,
+    svg_options: &SvgOptions,
+) -> Result<Vec<(ImageHolder, ImageHolder)>, IVCError> {
+    let pages_one = get_pages_from_file_location(image_location_one, svg_options)?;
+    let pages_two = get_pages_from_file_location(image_location_two, svg_options)?;
+
+    if pages_one.len() != pages_two.len() {
+        return Err(create_page_count_mismatch_error(
+            image_location_one,
+            pages_one.len(),
+            image_location_two,
+            pages_two.len(),
+        ));
+    }
+
+    Ok(pages_one.into_iter().zip(pages_two).collect())
+}
+
+/// Writes the image to the location, creating any missing parent directories, with the format
+/// chosen from the location's extension.
+pub fn save_image_to_file_location(
+    image: DynamicImage,
+    location: &str,
+) -> Result<ImageHolder, IVCError> {
+    create_parent_directories(location)?;
+
+    let write_image = || -> Result<(), ImageError> {
+        match ImageFormat::from_path(location) {
+            Ok(ImageFormat::WebP) => {
+                let rgba_image = image.to_rgba8();
+                WebPEncoder::new(BufWriter::new(File::create(location)?))
+                    .encode(
+                        &rgba_image,
+                        rgba_image.width(),
+                        rgba_image.height(),
+                        ColorType::Rgba8,
+                    )
+                    .map_err(|error| {
+                        ImageError::Encoding(EncodingError::new(
+                            ImageFormatHint::Exact(ImageFormat::WebP),
+                            error,
+                        ))
+                    })
+            }
+            Ok(ImageFormat::Jpeg) => DynamicImage::ImageRgb8(image.to_rgb8()).save(location),
+            _ => image.save(location),
+        }
+    };
+
+    match write_image() {
+        Ok(_) => Ok(ImageHolder::new(image, location)),
+        Err(error) => Err(create_io_write_error(location.to_owned(), error)),
+    }
+}
+
+/// Writes the rasterised image of each svg image in the pair alongside the mismatch location, such
+/// as `icon.original.png`, returning the locations written to.
+pub fn save_svg_rasters_of_pair(
+    (original_image_holder, latest_image_holder): &(ImageHolder, ImageHolder),
+    mismatched_location: &str,
+) -> Result<Vec<String>, IVCError> {
+    let mut raster_locations = Vec::new();
+
+    for (image_holder, image_name) in [
+        (original_image_holder, "original"),
+        (latest_image_holder, "latest"),
+    ] {
+        if !is_svg_location(&image_holder.location) {
+            continue;
+        }
+        let raster_location = get_svg_raster_location(mismatched_location, image_name);
+        save_image_to_file_location(image_holder.image.clone(), &raster_location)?;
+        raster_locations.push(raster_location);
+    }
+
+    Ok(raster_locations)
+}
+
+/// Writes the frames to the location as an animated gif, looping forever, creating any missing
+/// parent directories.
+pub fn save_animation_to_file_location(
+    frames: Vec<AnimationFrame>,
+    location: &str,
+) -> Result<ImageHolder, IVCError> {
+    create_parent_directories(location)?;
+
+    let write_frames = || -> Result<(), ImageError> {
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(location)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+        encoder.encode_frames(frames.iter().map(|frame| {
+            Frame::from_parts(
+                frame.image.to_rgba8(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame.delay_ms, 1),
+            )
+        }))
+    };
+
+    match write_frames() {
+        Ok(_) => Ok(ImageHolder::new(frames[0].image.clone(), location).with_frames(frames)),
+        Err(error) => Err(create_io_write_error(location.to_owned(), error)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    mod save_image_to_file_location {
+        mod returns_image_holder {
+            use crate::{
+                test_utils::{files::create_temp_dir_handler, image::create_dynamic_image},
+                utils::file_system::images::save_image_to_file_location,
+            };
+
+            #[test]
+            fn when_parent_directories_do_not_exist_yet() {
+                let temp_dir_holder = create_temp_dir_handler();
+                let location = temp_dir_holder.get_location_of_file_name("nested/dir/image.png");
+
+                let result = save_image_to_file_location(create_dynamic_image(5, 5), &location);
+
+                assert!(result.is_ok());
+                assert_eq!(location, result.unwrap().location);
+                assert!(std::path::Path::new(&location).is_file());
+            }
+
+            #[test]
+            fn when_writing_webp_and_jpeg_images() {
+                let temp_dir_holder = create_temp_dir_handler();
+
+                for (file_name, format) in [
+                    ("image.webp", image::ImageFormat::WebP),
+                    ("image.jpg", image::ImageFormat::Jpeg),
+                ] {
+                    let location = temp_dir_holder.get_location_of_file_name(file_name);
+
+                    let result = save_image_to_file_location(create_dynamic_image(5, 5), &location);
+
+                    assert!(result.is_ok());
+                    let written_image = image::open(&location).unwrap();
+                    assert_eq!((5, 5), (written_image.width(), written_image.height()));
+                    assert_eq!(
+                        Some(format),
+                        image::io::Reader::open(&location)
+                            .unwrap()
+                            .with_guessed_format()
+                            .unwrap()
+                            .format(),
+                        "{file_name}"
+                    );
+                }
+            }
+        }
+    }
+
+    mod save_svg_rasters_of_pair {
+        use std::path::Path;
+
+        use crate::{
+            models::SvgOptions,
+            test_utils::{files::create_temp_dir_handler, image::create_dynamic_image},
+            utils::file_system::images::{
+                get_pair_of_images_from_file_locations, save_svg_rasters_of_pair,
+            },
+        };
+
+        #[test]
+        fn writes_only_the_svg_images_of_the_pair() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let original_location = temp_dir_holder.get_location_of_file_name("original.png");
+            let latest_location = temp_dir_holder.get_location_of_file_name("latest.svg");
+            let mismatched_location =
+                temp_dir_holder.get_location_of_file_name("mismatched/icon.png");
+            create_dynamic_image(6, 3).save(&original_location).unwrap();
+            std::fs::write(
+                &latest_location,
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2\" height=\"1\"/>",
+            )
+            .unwrap();
+            let image_pair = get_pair_of_images_from_file_locations(
+                &original_location,
+                &latest_location,
+                &SvgOptions::default().with_scale(3_f32),
+            )
+            .unwrap();
+
+            let result = save_svg_rasters_of_pair(&image_pair, &mismatched_location).unwrap();
+
+            let raster_location =
+                temp_dir_holder.get_location_of_file_name("mismatched/icon.latest.png");
+            assert_eq!(vec![raster_location.to_owned()], result);
+            assert_eq!((6, 3), image::image_dimensions(&raster_location).unwrap());
+            assert!(!Path::new(
+                &temp_dir_holder.get_location_of_file_name("mismatched/icon.original.png")
+            )
+            .exists());
+        }
+    }
+
+    mod get_pairs_of_pages_from_file_locations {
+        use std::{borrow::Cow, fs::File};
+
+        use image::{GenericImageView, Rgba};
+        use tiff::{
+            encoder::{colortype::CMYK8, TiffEncoder, TiffValue},
+            tags::{Tag, Type},
+        };
+
+        use crate::{
+            models::SvgOptions,
+            test_utils::{
+                files::create_temp_dir_handler,
+                image::{create_dynamic_image, save_multi_page_tiff, SRGB_ICC_PROFILE},
+            },
+            utils::file_system::images::get_pairs_of_pages_from_file_locations,
+        };
+
+        #[test]
+        fn pairs_up_pages_of_multi_page_tiffs() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let location_one = temp_dir_holder.get_location_of_file_name("one.tiff");
+            let location_two = temp_dir_holder.get_location_of_file_name("two.tiff");
+            for location in [&location_one, &location_two] {
+                save_multi_page_tiff(
+                    &[create_dynamic_image(5, 5), create_dynamic_image(4, 3)],
+                    location,
+                );
+            }
+
+            let image_pairs = get_pairs_of_pages_from_file_locations(
+                &location_one,
+                &location_two,
+                &SvgOptions::default(),
+            )
+            .unwrap();
+
+            let locations: Vec<(&str, &str)> = image_pairs
+                .iter()
+                .map(|(one, two)| (one.location.as_str(), two.location.as_str()))
+                .collect();
+            assert_eq!(
+                vec![
+                    (
+                        format!("{location_one}#page1").as_str(),
+                        format!("{location_two}#page1").as_str()
+                    ),
+                    (
+                        format!("{location_one}#page2").as_str(),
+                        format!("{location_two}#page2").as_str()
+                    ),
+                ],
+                locations
+            );
+            assert_eq!(create_dynamic_image(4, 3), image_pairs[1].1.image);
+        }
+
+        #[test]
+        fn returns_single_pair_for_other_images() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let location_one = temp_dir_holder.get_location_of_file_name("one.tiff");
+            let location_two = temp_dir_holder.get_location_of_file_name("two.png");
+            save_multi_page_tiff(&[create_dynamic_image(5, 5)], &location_one);
+            create_dynamic_image(5, 5).save(&location_two).unwrap();
+
+            let image_pairs = get_pairs_of_pages_from_file_locations(
+                &location_one,
+                &location_two,
+                &SvgOptions::default(),
+            )
+            .unwrap();
+
+            assert_eq!(1, image_pairs.len());
+            assert_eq!(location_one, image_pairs[0].0.location);
+            assert_eq!(location_two, image_pairs[0].1.location);
+        }
+
+        // tiffs embed icc profiles as undefined bytes, whereas byte slices are written as bytes
+        struct IccProfileValue(&'static [u8]);
+
+        impl TiffValue for IccProfileValue {
+            const BYTE_LEN: u8 = 1;
+            const FIELD_TYPE: Type = Type::UNDEFINED;
+
+            fn count(&self) -> usize {
+                self.0.len()
+            }
+
+            fn data(&self) -> Cow<'_, [u8]> {
+                Cow::Borrowed(self.0)
+            }
+        }
+
+        #[test]
+        fn reads_cmyk_pages_and_embedded_icc_profiles_of_tiffs() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let location_one = temp_dir_holder.get_location_of_file_name("one.tiff");
+            let location_two = temp_dir_holder.get_location_of_file_name("two.tiff");
+            let mut encoder = TiffEncoder::new(File::create(&location_one).unwrap()).unwrap();
+            for cmyk in [[255, 0, 0, 0], [0, 0, 0, 255]] {
+                let mut page = encoder.new_image::<CMYK8>(1, 1).unwrap();
+                page.encoder()
+                    .write_tag(Tag::Unknown(34675), IccProfileValue(SRGB_ICC_PROFILE))
+                    .unwrap();
+                page.write_data(&cmyk).unwrap();
+            }
+            save_multi_page_tiff(
+                &[create_dynamic_image(1, 1), create_dynamic_image(1, 1)],
+                &location_two,
+            );
+
+            let image_pairs = get_pairs_of_pages_from_file_locations(
+                &location_one,
+                &location_two,
+                &SvgOptions::default(),
+            )
+            .unwrap();
+
+            let (first_page, second_page) = (&image_pairs[0].0, &image_pairs[1].0);
+            assert_eq!(Rgba([0, 255, 255, 255]), first_page.image.get_pixel(0, 0));
+            assert_eq!(Rgba([0, 0, 0, 255]), second_page.image.get_pixel(0, 0));
+            assert_eq!(
+                Some("sRGB"),
+                first_page
+                    .get_profile()
+                    .map(|profile| profile.name.as_str())
+            );
+            assert_eq!(None, image_pairs[0].1.get_profile());
+        }
+
+        #[test]
+        fn returns_error_when_page_counts_do_not_match() {
+            let temp_dir_holder = create_temp_dir_handler();
+            let location_one = temp_dir_holder.get_location_of_file_name("one.tiff");
+            let location_two = temp_dir_holder.get_location_of_file_name("two.tiff");
+            save_multi_page_tiff(
+                &[create_dynamic_image(5, 5), create_dynamic_image(5, 5)],
+                &location_one,
+            );
+            save_multi_page_tiff(
+                &[
+                    create_dynamic_image(5, 5),
+                    create_dynamic_image(5, 5),
+                    create_dynamic_image(5, 5),
+                ],
+                &location_two,
+            );
+
+            let result = get_pairs_of_pages_from_file_locations(
+                &location_one,
+                &location_two,
+                &SvgOptions::default(),
+            );
+
+            assert_eq!(
+                format!("Number of pages in multi-page images do not match: '{location_one}' has '2', '{location_two}' has '3'."),
+                result.unwrap_err().to_string()
+            );
+        }
+    }
+
     mod get_pair_of_images_from_file_locations {
 
         mod returns_error {
 
             use crate::{
+                models::SvgOptions,
                 test_utils::{
                     files::{create_temp_dir_handler, get_image_locations},
                     image::create_dynamic_image,
@@ -35,6 +418,7 @@ mod tests {
                 let result = get_pair_of_images_from_file_locations(
                     image_one_location.as_str(),
                     image_two_location.as_str(),
+                    &SvgOptions::default(),
                 );
 
                 let expected_message = format!(
@@ -57,6 +441,7 @@ mod tests {
                 let result = get_pair_of_images_from_file_locations(
                     image_one_location.as_str(),
                     image_two_location.as_str(),
+                    &SvgOptions::default(),
                 );
 
                 let expected_err_msg = format!(
@@ -79,6 +464,7 @@ mod tests {
                 let result = get_pair_of_images_from_file_locations(
                     image_one_location.as_str(),
                     image_two_location.as_str(),
+                    &SvgOptions::default(),
                 );
 
                 let expected_err_msg = format!(
@@ -92,8 +478,10 @@ mod tests {
         }
 
         mod returns_images {
+            use image::ImageFormat;
+
             use crate::{
-                models::ImageHolder,
+                models::{ImageHolder, SvgOptions},
                 test_utils::{
                     files::{create_temp_dir_handler, get_image_locations},
                     image::{change_pixel_on_img, create_dynamic_image},
@@ -116,6 +504,7 @@ mod tests {
                 let result = get_pair_of_images_from_file_locations(
                     image_one_location.as_str(),
                     image_two_location.as_str(),
+                    &SvgOptions::default(),
                 );
 
                 let expected = (
@@ -126,6 +515,34 @@ mod tests {
                 assert_eq!(expected, result.unwrap());
             }
 
+            #[test]
+            fn when_image_extensions_do_not_match_their_content() {
+                let temp_dir_holder = create_temp_dir_handler();
+                let image_one_location = temp_dir_holder.get_location_of_file_name("png.jpg");
+                let image_two_location = temp_dir_holder.get_location_of_file_name("png.webp");
+
+                let image = create_dynamic_image(5, 5);
+                image
+                    .save_with_format(&image_one_location, ImageFormat::Png)
+                    .unwrap();
+                image
+                    .save_with_format(&image_two_location, ImageFormat::Png)
+                    .unwrap();
+
+                let result = get_pair_of_images_from_file_locations(
+                    image_one_location.as_str(),
+                    image_two_location.as_str(),
+                    &SvgOptions::default(),
+                );
+
+                let expected = (
+                    ImageHolder::new(image.clone(), &image_one_location),
+                    ImageHolder::new(image, &image_two_location),
+                );
+
+                assert_eq!(expected, result.unwrap());
+            }
+
             #[test]
             fn when_images_exist_but_do_not_match_in_content() {
                 let temp_dir_holder = create_temp_dir_handler();
@@ -142,6 +559,7 @@ mod tests {
                 let result = get_pair_of_images_from_file_locations(
                     image_one_location.as_str(),
                     image_two_location.as_str(),
+                    &SvgOptions::default(),
                 );
 
                 let expected = (
@@ -156,19 +574,331 @@ mod tests {
 }
 
 mod helpers {
-    use std::path::Path;
+    use std::{
+        fs,
+        io::{self, Cursor},
+        path::Path,
+    };
+
+    use image::{
+        codecs::{gif::GifDecoder, jpeg::JpegDecoder, png::PngDecoder},
+        AnimationDecoder, DynamicImage, Frame, ImageBuffer, ImageDecoder, ImageError, ImageFormat,
+    };
+    use resvg::{
+        tiny_skia::{Pixmap, Transform},
+        usvg::{Options, Tree},
+    };
+    use tiff::{
+        decoder::{Decoder as TiffDecoder, DecodingResult},
+        tags::Tag,
+        ColorType, TiffError,
+    };
 
     use crate::{
-        errors::{handling::create_io_read_error, ivc::IVCError},
-        models::ImageHolder,
+        errors::{
+            handling::{create_io_read_error, create_io_write_error},
+            ivc::IVCError,
+        },
+        models::{AnimationFrame, ImageHolder, SvgOptions},
+        utils::{
+            colour_profile::create_colour_profile,
+            file_paths::{get_page_location, is_svg_location},
+        },
     };
 
-    pub fn get_image_from_file_location(location: &str) -> Result<ImageHolder, IVCError> {
+    // the tag holding an icc profile embedded in a tiff page, which the tiff crate does not name
+    const TIFF_ICC_PROFILE_TAG: u16 = 34675;
+
+    pub fn create_parent_directories(location: &str) -> Result<(), IVCError> {
+        match Path::new(location).parent() {
+            Some(parent) => fs::create_dir_all(parent).map_err(|error| {
+                create_io_write_error(location.to_owned(), ImageError::IoError(error))
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads the image at the location, with its format detected from the file's content rather
+    /// than its extension. Every frame of an animated gif or png is read as well, along with the
+    /// icc colour profile embedded in a png or jpeg.
+    ///
+    /// Svg images, known by their extension, are rasterised with the svg options instead.
+    pub fn get_image_from_file_location(
+        location: &str,
+        svg_options: &SvgOptions,
+    ) -> Result<ImageHolder, IVCError> {
         let path = Path::new(location);
 
-        match image::open(path) {
-            Ok(image) => Ok(ImageHolder::new(image, location)),
-            Err(error) => Err(create_io_read_error(location.to_owned(), error)),
+        let decoded = match is_svg_location(location) {
+            true => rasterise_svg(path, svg_options)
+                .map(|image| ImageHolder::new(image, &path.to_string_lossy())),
+            false => fs::read(path)
+                .map_err(ImageError::IoError)
+                .and_then(|data| {
+                    let format = image::guess_format(&data).ok();
+                    decode_image_by_content(&data, format, &path.to_string_lossy())
+                }),
+        };
+
+        decoded.map_err(|error| create_io_read_error(location.to_owned(), error))
+    }
+
+    /// Reads every page of the image at the location when it is a multi-page tiff, each with the
+    /// location of its page, otherwise the image alone.
+    pub fn get_pages_from_file_location(
+        location: &str,
+        svg_options: &SvgOptions,
+    ) -> Result<Vec<ImageHolder>, IVCError> {
+        if is_svg_location(location) {
+            return Ok(vec![get_image_from_file_location(location, svg_options)?]);
         }
+
+        decode_pages_by_content(location)
+            .map_err(|error| create_io_read_error(location.to_owned(), error))
+    }
+
+    fn decode_pages_by_content(location: &str) -> Result<Vec<ImageHolder>, ImageError> {
+        let data = fs::read(location)?;
+        let format = image::guess_format(&data).ok();
+        if format != Some(ImageFormat::Tiff) {
+            return Ok(vec![decode_image_by_content(&data, format, location)?]);
+        }
+
+        decode_tiff_pages(&data, location, true)
+    }
+
+    /// Decodes the image once with the decoder of its sniffed format, an animation's first frame
+    /// being its image.
+    fn decode_image_by_content(
+        data: &[u8],
+        format: Option<ImageFormat>,
+        location: &str,
+    ) -> Result<ImageHolder, ImageError> {
+        let (image, frames, icc_profile) = match format {
+            Some(ImageFormat::Gif) => {
+                let frames = GifDecoder::new(Cursor::new(data))?
+                    .into_frames()
+                    .collect_frames()?;
+                (get_first_frame_image(&frames)?, frames, None)
+            }
+            Some(ImageFormat::Png) => {
+                let mut decoder = PngDecoder::new(Cursor::new(data))?;
+                let icc_profile = decoder.icc_profile();
+                match decoder.is_apng() {
+                    true => {
+                        let frames = decoder.apng().into_frames().collect_frames()?;
+                        (get_first_frame_image(&frames)?, frames, icc_profile)
+                    }
+                    false => (
+                        DynamicImage::from_decoder(decoder)?,
+                        Vec::new(),
+                        icc_profile,
+                    ),
+                }
+            }
+            Some(ImageFormat::Tiff) => {
+                return Ok(decode_tiff_pages(data, location, false)?.remove(0))
+            }
+            Some(ImageFormat::Jpeg) => {
+                let mut decoder = JpegDecoder::new(Cursor::new(data))?;
+                let icc_profile = decoder.icc_profile();
+                (
+                    DynamicImage::from_decoder(decoder)?,
+                    Vec::new(),
+                    icc_profile,
+                )
+            }
+            Some(format) => (
+                image::load_from_memory_with_format(data, format)?,
+                Vec::new(),
+                None,
+            ),
+            None => (image::load_from_memory(data)?, Vec::new(), None),
+        };
+
+        let image_holder =
+            ImageHolder::new(image, location).with_profile(icc_profile.map(create_colour_profile));
+        if frames.len() < 2 {
+            return Ok(image_holder);
+        }
+
+        Ok(image_holder.with_frames(frames.into_iter().map(to_animation_frame).collect()))
+    }
+
+    fn get_first_frame_image(frames: &[Frame]) -> Result<DynamicImage, ImageError> {
+        frames
+            .first()
+            .map(|frame| DynamicImage::ImageRgba8(frame.buffer().clone()))
+            .ok_or_else(|| to_invalid_data_error("animation has no frames".to_string()))
+    }
+
+    /// Renders the svg at its own size multiplied by the scale, resolving any relative resources,
+    /// such as embedded images, against the svg's directory.
+    fn rasterise_svg(path: &Path, svg_options: &SvgOptions) -> Result<DynamicImage, ImageError> {
+        let data = fs::read(path)?;
+        let options = Options {
+            resources_dir: path.parent().map(Path::to_path_buf),
+            dpi: svg_options.get_dpi(),
+            ..Options::default()
+        };
+        let tree = Tree::from_data(&data, &options)
+            .map_err(|error| to_invalid_data_error(error.to_string()))?;
+
+        let scale = svg_options.get_scale();
+        let size =
+            tree.size().to_int_size().scale_by(scale).ok_or_else(|| {
+                to_invalid_data_error(format!("svg cannot be scaled by '{scale}'"))
+            })?;
+        let mut pixmap = Pixmap::new(size.width(), size.height()).ok_or_else(|| {
+            to_invalid_data_error(format!(
+                "svg cannot be rasterised at '{}x{}'",
+                size.width(),
+                size.height()
+            ))
+        })?;
+        resvg::render(
+            &tree,
+            Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+
+        // pixmaps hold premultiplied alpha, while rgba images do not
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let colour = pixel.demultiply();
+                [colour.red(), colour.green(), colour.blue(), colour.alpha()]
+            })
+            .collect();
+
+        ImageBuffer::from_raw(size.width(), size.height(), pixels)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| to_invalid_data_error("svg raster does not match its size".to_string()))
+    }
+
+    /// Decodes the tiff's pages, or only its first, each along with its embedded icc profile and
+    /// having the location of its page when there are several.
+    ///
+    /// Any page this decoder cannot read is decoded by the image crate's tiff decoder instead, when
+    /// it is the first page read alone or the tiff's only page, as that decoder reads only the
+    /// first.
+    fn decode_tiff_pages(
+        data: &[u8],
+        location: &str,
+        is_reading_every_page: bool,
+    ) -> Result<Vec<ImageHolder>, ImageError> {
+        let mut decoder = TiffDecoder::new(Cursor::new(data)).map_err(to_image_error)?;
+        let mut pages = Vec::new();
+
+        loop {
+            let icc_profile = decoder
+                .get_tag_u8_vec(Tag::Unknown(TIFF_ICC_PROFILE_TAG))
+                .ok();
+            let is_last_page = !is_reading_every_page || !decoder.more_images();
+            let page = match decode_tiff_page(&mut decoder) {
+                Ok(page) => page,
+                Err(_) if pages.is_empty() && is_last_page => {
+                    image::load_from_memory_with_format(data, ImageFormat::Tiff)?
+                }
+                Err(error) => return Err(error),
+            };
+            pages.push((page, icc_profile));
+
+            if is_last_page {
+                break;
+            }
+            decoder.next_image().map_err(to_image_error)?;
+        }
+
+        let page_count = pages.len();
+        Ok(pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, (page, icc_profile))| {
+                let page_location = match page_count {
+                    1 => location.to_owned(),
+                    _ => get_page_location(location, index + 1),
+                };
+                ImageHolder::new(page, &page_location)
+                    .with_profile(icc_profile.map(create_colour_profile))
+            })
+            .collect())
+    }
+
+    fn decode_tiff_page(
+        decoder: &mut TiffDecoder<Cursor<&[u8]>>,
+    ) -> Result<DynamicImage, ImageError> {
+        let (width, height) = decoder.dimensions().map_err(to_image_error)?;
+        let colour_type = decoder.colortype().map_err(to_image_error)?;
+
+        let page = match (colour_type, decoder.read_image().map_err(to_image_error)?) {
+            (ColorType::Gray(8), DecodingResult::U8(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma8)
+            }
+            (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA8)
+            }
+            (ColorType::RGB(8), DecodingResult::U8(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb8)
+            }
+            (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba8)
+            }
+            (ColorType::CMYK(8), DecodingResult::U8(data)) => {
+                let rgb_data = data.chunks_exact(4).flat_map(cmyk_to_rgb).collect();
+                ImageBuffer::from_raw(width, height, rgb_data).map(DynamicImage::ImageRgb8)
+            }
+            (ColorType::Gray(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLuma16)
+            }
+            (ColorType::GrayA(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageLumaA16)
+            }
+            (ColorType::RGB(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb16)
+            }
+            (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba16)
+            }
+            (ColorType::RGB(32), DecodingResult::F32(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgb32F)
+            }
+            (ColorType::RGBA(32), DecodingResult::F32(data)) => {
+                ImageBuffer::from_raw(width, height, data).map(DynamicImage::ImageRgba32F)
+            }
+            _ => None,
+        };
+
+        page.ok_or_else(|| {
+            to_invalid_data_error(format!(
+                "unsupported tiff page colour type: '{colour_type:?}'"
+            ))
+        })
+    }
+
+    /// Converts without a colour profile, each of cyan, magenta and yellow taking away from red,
+    /// green and blue, along with black from all three.
+    fn cmyk_to_rgb(cmyk: &[u8]) -> [u8; 3] {
+        let remaining_black = 255 - cmyk[3] as u16;
+
+        [0, 1, 2].map(|index| ((255 - cmyk[index] as u16) * remaining_black / 255) as u8)
+    }
+
+    fn to_image_error(error: TiffError) -> ImageError {
+        to_invalid_data_error(error.to_string())
+    }
+
+    fn to_invalid_data_error(message: String) -> ImageError {
+        ImageError::IoError(io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+
+    fn to_animation_frame(frame: Frame) -> AnimationFrame {
+        let (numerator, denominator) = frame.delay().numer_denom_ms();
+
+        AnimationFrame::new(
+            DynamicImage::ImageRgba8(frame.into_buffer()),
+            numerator / denominator.max(1),
+        )
     }
 }
@@ -0,0 +1,29 @@
+use std::fs;
+
+use image::ImageError;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::{
+    handling::{create_io_read_error, create_io_write_error, create_json_file_error},
+    ivc::IVCError,
+};
+
+use super::images::create_parent_directories;
+
+pub fn write_json_file<T: Serialize>(location: &str, value: &T) -> Result<(), IVCError> {
+    create_parent_directories(location)?;
+
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|error| create_json_file_error(location.to_owned(), error))?;
+
+    fs::write(location, contents)
+        .map_err(|error| create_io_write_error(location.to_owned(), ImageError::IoError(error)))
+}
+
+pub fn read_json_file<T: DeserializeOwned>(location: &str) -> Result<T, IVCError> {
+    let contents = fs::read_to_string(location)
+        .map_err(|error| create_io_read_error(location.to_owned(), ImageError::IoError(error)))?;
+
+    serde_json::from_str(&contents)
+        .map_err(|error| create_json_file_error(location.to_owned(), error))
+}
@@ -1,3 +1,7 @@
+pub mod baselines;
 pub mod directories;
 pub mod files;
+pub mod history;
 pub mod images;
+pub mod json;
+pub mod results;
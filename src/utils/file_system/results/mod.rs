@@ -0,0 +1,163 @@
+use std::fs;
+
+use image::ImageError;
+
+use crate::{
+    config::CompareConfig,
+    errors::{handling::create_io_write_error, ivc::IVCError},
+    logger::Logger,
+    models::RunResults,
+    utils::{markdown::render_markdown_summary, report::render_html_report},
+};
+
+use super::{
+    images::create_parent_directories,
+    json::{read_json_file, write_json_file},
+};
+
+pub fn write_run_results(location: &str, run_results: &RunResults) -> Result<(), IVCError> {
+    write_json_file(location, run_results)?;
+
+    Logger::info(format!("Wrote results file: '{location}'"));
+
+    Ok(())
+}
+
+pub fn write_html_report(location: &str, run_results: &RunResults) -> Result<(), IVCError> {
+    create_parent_directories(location)?;
+
+    fs::write(location, render_html_report(run_results, location))
+        .map_err(|error| create_io_write_error(location.to_owned(), ImageError::IoError(error)))?;
+
+    Logger::info(format!("Wrote html report: '{location}'"));
+
+    Ok(())
+}
+
+pub fn write_markdown_summary(location: &str, run_results: &RunResults) -> Result<(), IVCError> {
+    create_parent_directories(location)?;
+
+    fs::write(location, render_markdown_summary(run_results, location))
+        .map_err(|error| create_io_write_error(location.to_owned(), ImageError::IoError(error)))?;
+
+    Logger::info(format!("Wrote markdown summary: '{location}'"));
+
+    Ok(())
+}
+
+/// Writes both the results file and the html report for the run, along with the markdown summary
+/// when one is configured, to their configured locations.
+pub fn write_run_outputs(config: &CompareConfig, run_results: &RunResults) -> Result<(), IVCError> {
+    write_run_results(&config.get_results_file_location(), run_results)?;
+    write_html_report(&config.get_report_file_location(), run_results)?;
+
+    match config.get_markdown_file_location() {
+        Some(location) => write_markdown_summary(location, run_results),
+        None => Ok(()),
+    }
+}
+
+pub fn read_run_results(location: &str) -> Result<RunResults, IVCError> {
+    read_json_file(location)
+}
+
+#[cfg(test)]
+mod tests {
+    mod write_run_outputs {
+        use std::fs;
+
+        use crate::{
+            config::CompareConfig, models::RunResults, test_utils::files::create_temp_dir_handler,
+            utils::file_system::results::write_run_outputs,
+        };
+
+        #[test]
+        fn writes_markdown_summary_only_when_configured() {
+            let temp_dir_handler = create_temp_dir_handler();
+            let output_dir = temp_dir_handler.get_location_of_file_name("mismatched");
+            let markdown_location = temp_dir_handler.get_location_of_file_name("pr/summary.md");
+            let config = CompareConfig::builder().output_dir(&output_dir).build();
+            let markdown_config = CompareConfig::builder()
+                .output_dir(&output_dir)
+                .markdown_file(&markdown_location)
+                .build();
+
+            write_run_outputs(&config, &RunResults::default()).unwrap();
+            assert!(!std::path::Path::new(&markdown_location).exists());
+
+            write_run_outputs(&markdown_config, &RunResults::default()).unwrap();
+            assert!(fs::read_to_string(&markdown_location)
+                .unwrap()
+                .starts_with("## Image visual comparison\n"));
+        }
+    }
+
+    mod read_run_results {
+        mod returns_error {
+            use std::fs;
+
+            use crate::{
+                test_utils::files::create_temp_dir_handler,
+                utils::file_system::results::read_run_results,
+            };
+
+            #[test]
+            fn when_results_file_does_not_exist() {
+                let temp_dir_handler = create_temp_dir_handler();
+                let location = temp_dir_handler.get_location_of_file_name("results.json");
+
+                let result = read_run_results(&location);
+
+                assert_eq!(
+                    format!("Issue parsing file at location: '{location}'. Message: 'No such file or directory (os error 2)'"),
+                    result.unwrap_err().to_string()
+                );
+            }
+
+            #[test]
+            fn when_results_file_is_not_valid() {
+                let temp_dir_handler = create_temp_dir_handler();
+                let location = temp_dir_handler.get_location_of_file_name("results.json");
+                fs::write(&location, "{").unwrap();
+
+                let result = read_run_results(&location);
+
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .starts_with(&format!("Issue with JSON file at location: '{location}'")));
+            }
+        }
+
+        mod returns_results {
+            use crate::{
+                models::{PairResult, PairStatus, RunResults},
+                test_utils::{files::create_temp_dir_handler, results::create_pair_result},
+                utils::file_system::results::{read_run_results, write_run_results},
+            };
+
+            #[test]
+            fn when_results_were_written_to_the_location() {
+                let temp_dir_handler = create_temp_dir_handler();
+                let location = temp_dir_handler.get_location_of_file_name("nested/results.json");
+                let run_results = RunResults {
+                    pairs: vec![PairResult {
+                        mismatched_pixel_count: 2,
+                        diff: Some("mismatched/a.png".to_string()),
+                        ..create_pair_result(
+                            "original/a.png",
+                            "latest/a.png",
+                            PairStatus::Mismatched,
+                        )
+                    }],
+                    new_images: vec!["latest/b.png".to_string()],
+                    missing_images: vec![],
+                };
+
+                write_run_results(&location, &run_results).unwrap();
+
+                assert_eq!(run_results, read_run_results(&location).unwrap());
+            }
+        }
+    }
+}
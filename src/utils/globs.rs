@@ -0,0 +1,54 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::errors::{handling::create_invalid_glob_error, ivc::IVCError};
+
+/// Compiles the patterns into a single set, matched against image paths relative to the
+/// directory they were found in.
+pub fn create_glob_set(patterns: &[String]) -> Result<GlobSet, IVCError> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns.iter() {
+        let glob = Glob::new(pattern).map_err(|error| create_invalid_glob_error(pattern, error))?;
+        builder.add(glob);
+    }
+
+    builder
+        .build()
+        .map_err(|error| create_invalid_glob_error(&patterns.join(", "), error))
+}
+
+/// Whether the path matches any of the patterns, with invalid patterns never matching.
+pub fn is_matching_any_glob(patterns: &[String], path: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        Glob::new(pattern)
+            .map(|glob| glob.compile_matcher().is_match(path))
+            .unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_glob_set {
+        use crate::utils::globs::create_glob_set;
+
+        #[test]
+        fn returns_set_matching_relative_paths() {
+            let glob_set =
+                create_glob_set(&["checkout/**".to_string(), "*.webp".to_string()]).unwrap();
+
+            assert!(glob_set.is_match("checkout/cart/empty.png"));
+            assert!(glob_set.is_match("home.webp"));
+            assert!(!glob_set.is_match("home.png"));
+        }
+
+        #[test]
+        fn returns_error_for_invalid_pattern() {
+            let result = create_glob_set(&["checkout/[".to_string()]);
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Invalid glob pattern: 'checkout/['."));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use image::{imageops, DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::models::{ColourMetric, CompareOptions, PixelCoord};
+
+use super::{
+    colour::{get_colour_difference_for_img_pixels, get_native_colour_difference_for_img_pixels},
+    image::is_comparing_at_native_precision,
+};
+
+// from no difference to the largest difference on the heatmap's scale
+const HEATMAP_RAMP: [[u8; 3]; 5] = [
+    [0, 0, 255],
+    [0, 255, 255],
+    [0, 255, 0],
+    [255, 255, 0],
+    [255, 0, 0],
+];
+const LEGEND_HEIGHT: u32 = 28;
+const LEGEND_BACKGROUND: Rgba<u8> = Rgba([32, 32, 32, 255]);
+const LEGEND_FOREGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LEGEND_BAR_ROWS: std::ops::Range<u32> = 4..12;
+const LEGEND_TICK_ROWS: std::ops::Range<u32> = 2..14;
+const LEGEND_LABEL_TOP: u32 = 16;
+const GLYPH_SCALE: u32 = 2;
+// each digit is 3 pixels wide and 5 tall, a row per entry with its leftmost pixel as the highest bit
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// The difference, as measured by the metric, shown at the hot end of the heatmap's colour ramp,
+/// such as a black pixel becoming white. Larger differences are shown at the hot end too.
+pub fn get_heatmap_scale(metric: ColourMetric) -> f32 {
+    match metric {
+        ColourMetric::LabSquared => 10000_f32,
+        ColourMetric::Lab => 100_f32,
+        ColourMetric::Rgba => 255_f32,
+    }
+}
+
+/// Creates a heatmap of the pair, each differing pixel coloured by how much it differs, as
+/// measured by the options' metric and precision, from blue for the slightest difference to red
+/// for the heatmap scale or more. Pixels that do not differ, or are masked, show the latest image
+/// darkened in greyscale.
+///
+/// The ramp follows the square root of the difference over the scale, so that subtle differences
+/// are spread further apart than large ones.
+pub fn create_heatmap_image(
+    original_image: &DynamicImage,
+    latest_image: &DynamicImage,
+    options: &CompareOptions,
+) -> DynamicImage {
+    let scale = get_heatmap_scale(options.get_metric());
+    let latest_luma = latest_image.to_luma8();
+    let native_images = is_comparing_at_native_precision(original_image, latest_image, options)
+        .then(|| (original_image.to_rgba32f(), latest_image.to_rgba32f()));
+
+    let get_difference = |pixel_coord: &PixelCoord| match &native_images {
+        Some((original_image, latest_image)) => get_native_colour_difference_for_img_pixels(
+            original_image,
+            latest_image,
+            pixel_coord,
+            options.get_metric(),
+        ),
+        None => get_colour_difference_for_img_pixels(
+            original_image,
+            latest_image,
+            pixel_coord,
+            options.get_metric(),
+        ),
+    };
+
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        latest_image.width(),
+        latest_image.height(),
+        |x, y| {
+            let pixel_coord = PixelCoord::new(x, y);
+            let difference = match options.is_masked(&pixel_coord) {
+                true => 0_f32,
+                false => get_difference(&pixel_coord),
+            };
+
+            if difference > 0_f32 {
+                get_ramp_colour(get_ramp_fraction(difference, scale))
+            } else {
+                let grey = latest_luma.get_pixel(x, y).0[0] / 3;
+                Rgba([grey, grey, grey, 255])
+            }
+        },
+    ))
+}
+
+/// Adds a legend beneath the heatmap, showing the colour ramp from no difference, on the left, to
+/// the heatmap scale, on the right, with a tick where the tolerance falls on it.
+pub fn add_heatmap_legend(heatmap_image: &DynamicImage, options: &CompareOptions) -> DynamicImage {
+    let (width, height) = heatmap_image.dimensions();
+    let scale = get_heatmap_scale(options.get_metric());
+    let mut image_with_legend =
+        RgbaImage::from_pixel(width, height + LEGEND_HEIGHT, LEGEND_BACKGROUND);
+
+    imageops::replace(&mut image_with_legend, &heatmap_image.to_rgba8(), 0, 0);
+
+    let last_x = width.saturating_sub(1).max(1) as f32;
+    for x in 0..width {
+        let colour = get_ramp_colour(x as f32 / last_x);
+        for y in LEGEND_BAR_ROWS {
+            image_with_legend.put_pixel(x, height + y, colour);
+        }
+    }
+
+    let tolerance_x = (get_ramp_fraction(options.get_tolerance(), scale) * last_x).round() as u32;
+    if tolerance_x < width {
+        for y in LEGEND_TICK_ROWS {
+            image_with_legend.put_pixel(tolerance_x, height + y, LEGEND_FOREGROUND);
+        }
+    }
+
+    let min_label = "0";
+    let max_label = format!("{scale}");
+    let max_label_width = get_label_width(&max_label);
+    // labels are left out when the image is too narrow for them to sit apart
+    if get_label_width(min_label) + GLYPH_SCALE * 2 + max_label_width <= width {
+        draw_label(
+            &mut image_with_legend,
+            min_label,
+            0,
+            height + LEGEND_LABEL_TOP,
+        );
+        draw_label(
+            &mut image_with_legend,
+            &max_label,
+            width - max_label_width,
+            height + LEGEND_LABEL_TOP,
+        );
+    }
+
+    DynamicImage::ImageRgba8(image_with_legend)
+}
+
+fn get_ramp_fraction(difference: f32, scale: f32) -> f32 {
+    (difference / scale).clamp(0_f32, 1_f32).sqrt()
+}
+
+fn get_ramp_colour(fraction: f32) -> Rgba<u8> {
+    let position = fraction.clamp(0_f32, 1_f32) * (HEATMAP_RAMP.len() - 1) as f32;
+    let index = (position.floor() as usize).min(HEATMAP_RAMP.len() - 2);
+    let weight = position - index as f32;
+    let (from, to) = (HEATMAP_RAMP[index], HEATMAP_RAMP[index + 1]);
+
+    let channel = |channel: usize| {
+        (from[channel] as f32 + (to[channel] as f32 - from[channel] as f32) * weight).round() as u8
+    };
+
+    Rgba([channel(0), channel(1), channel(2), 255])
+}
+
+fn get_label_width(label: &str) -> u32 {
+    // a gap of one glyph pixel between each digit
+    (label.len() as u32 * 4).saturating_sub(1) * GLYPH_SCALE
+}
+
+/// Draws the digits of the label, left to right from the co-ordinate, skipping any other
+/// characters.
+fn draw_label(image: &mut RgbaImage, label: &str, left: u32, top: u32) {
+    for (index, digit) in label.chars().enumerate() {
+        let Some(glyph) = digit.to_digit(10).map(|digit| DIGIT_GLYPHS[digit as usize]) else {
+            continue;
+        };
+        let glyph_left = left + index as u32 * 4 * GLYPH_SCALE;
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for offset_y in 0..GLYPH_SCALE {
+                    for offset_x in 0..GLYPH_SCALE {
+                        let x = glyph_left + column * GLYPH_SCALE + offset_x;
+                        let y = top + row as u32 * GLYPH_SCALE + offset_y;
+                        if x < image.width() && y < image.height() {
+                            image.put_pixel(x, y, LEGEND_FOREGROUND);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_heatmap_image {
+        use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+        use crate::{
+            models::{ColourMetric, CompareOptions, Mask},
+            utils::heatmap::create_heatmap_image,
+        };
+
+        #[test]
+        fn colours_pixels_by_how_much_they_differ() {
+            let original =
+                DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 1, Rgba([0, 0, 0, 255])));
+            let mut latest = original.clone();
+            latest
+                .as_mut_rgba8()
+                .unwrap()
+                .put_pixel(1, 0, Rgba([3, 0, 0, 255]));
+            latest
+                .as_mut_rgba8()
+                .unwrap()
+                .put_pixel(2, 0, Rgba([255, 255, 255, 255]));
+            latest
+                .as_mut_rgba8()
+                .unwrap()
+                .put_pixel(3, 0, Rgba([255, 255, 255, 255]));
+
+            let result = create_heatmap_image(
+                &original,
+                &latest,
+                &CompareOptions::new(0_f32)
+                    .with_metric(ColourMetric::Rgba)
+                    .with_masks([Mask::new(3, 0, 1, 1)]),
+            );
+
+            assert_eq!(Rgba([0, 0, 0, 255]), result.get_pixel(0, 0));
+            assert_eq!(Rgba([0, 111, 255, 255]), result.get_pixel(1, 0));
+            assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(2, 0));
+            assert_eq!(Rgba([85, 85, 85, 255]), result.get_pixel(3, 0));
+        }
+    }
+
+    mod add_heatmap_legend {
+        use image::{DynamicImage, GenericImageView, Rgba};
+
+        use crate::{
+            models::{ColourMetric, CompareOptions},
+            utils::heatmap::{add_heatmap_legend, LEGEND_FOREGROUND, LEGEND_HEIGHT},
+        };
+
+        #[test]
+        fn adds_colour_ramp_with_tolerance_tick_beneath_the_heatmap() {
+            let heatmap = DynamicImage::new_rgba8(60, 10);
+
+            let result = add_heatmap_legend(
+                &heatmap,
+                &CompareOptions::new(255_f32).with_metric(ColourMetric::Rgba),
+            );
+
+            assert_eq!((60, 10 + LEGEND_HEIGHT), result.dimensions());
+            assert_eq!(heatmap.get_pixel(5, 5), result.get_pixel(5, 5));
+            assert_eq!(Rgba([0, 0, 255, 255]), result.get_pixel(0, 15));
+            assert_eq!(LEGEND_FOREGROUND, result.get_pixel(59, 12));
+            // the top left pixel of the '0' label
+            assert_eq!(LEGEND_FOREGROUND, result.get_pixel(0, 26));
+        }
+    }
+}
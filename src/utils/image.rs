@@ -1,8 +1,25 @@
-use lab::Lab;
-
-use crate::models::{ImageHolder, PixelCoord};
-
-use super::colour::get_lab_colour_for_img_pixel;
+use crate::models::{
+    AnimationFrame, ColourPrecision, CompareOptions, DiffLayout, HighlightColour, ImageHolder,
+    MismatchRegion, PixelCoord,
+};
+use image::{
+    imageops, DynamicImage, GenericImage, GenericImageView, Pixel, Rgba, Rgba32FImage, RgbaImage,
+};
+
+use super::{
+    colour::{
+        get_colour_difference_for_img_pixels, get_native_colour_difference_for_img_pixels,
+        is_high_bit_depth_image,
+    },
+    heatmap::{add_heatmap_legend, create_heatmap_image},
+};
+
+const BLINK_DELAY_MS: u32 = 500;
+const MAX_CROP_REGIONS: usize = 20;
+// pixels of context kept around each region before it is scaled up
+const CROP_PADDING: u32 = 8;
+const CROP_TARGET_SIZE: u32 = 256;
+const MAX_CROP_SCALE: u32 = 16;
 
 pub fn are_dimensions_matching_for_images(
     (image_one_holder, image_two_holder): &(ImageHolder, ImageHolder),
@@ -13,15 +30,403 @@ pub fn are_dimensions_matching_for_images(
 }
 
 pub fn is_pixel_for_images_matching(
-    (image_one_holder, image_two_holder): &(ImageHolder, ImageHolder),
+    image_one: &DynamicImage,
+    image_two: &DynamicImage,
     pixel_coord: &PixelCoord,
-    tolerance: f32,
+    options: &CompareOptions,
+) -> bool {
+    let difference = get_colour_difference_for_img_pixels(
+        image_one,
+        image_two,
+        pixel_coord,
+        options.get_metric(),
+    );
+
+    difference <= options.get_tolerance()
+}
+
+/// Whether the pair is compared at native precision, which is only when set by the options and
+/// either image has more than 8 bits per channel.
+pub fn is_comparing_at_native_precision(
+    image_one: &DynamicImage,
+    image_two: &DynamicImage,
+    options: &CompareOptions,
 ) -> bool {
-    let lab_colour: Lab = get_lab_colour_for_img_pixel(&image_one_holder.image, pixel_coord);
-    let lab_colour_two: Lab = get_lab_colour_for_img_pixel(&image_two_holder.image, pixel_coord);
-    let difference: f32 = lab_colour.squared_distance(&lab_colour_two);
+    options.get_precision() == ColourPrecision::Native
+        && (is_high_bit_depth_image(image_one) || is_high_bit_depth_image(image_two))
+}
+
+/// Whether the pixels at the co-ordinate match within the tolerance, compared at the native
+/// precision of the images, which are expected to be converted via `DynamicImage::to_rgba32f`.
+pub fn is_native_pixel_for_images_matching(
+    image_one: &Rgba32FImage,
+    image_two: &Rgba32FImage,
+    pixel_coord: &PixelCoord,
+    options: &CompareOptions,
+) -> bool {
+    let difference = get_native_colour_difference_for_img_pixels(
+        image_one,
+        image_two,
+        pixel_coord,
+        options.get_metric(),
+    );
+
+    difference <= options.get_tolerance()
+}
+
+/// The percentage (0 - 100) of the pixels that are mismatched, 0 for images without any pixels.
+pub fn get_mismatch_percentage(mismatched_pixel_count: usize, pixel_count: u64) -> f32 {
+    match pixel_count {
+        0 => 0_f32,
+        pixel_count => mismatched_pixel_count as f32 / pixel_count as f32 * 100_f32,
+    }
+}
+
+/// Creates the diff image of the pair, laid out and styled by the diff options.
+///
+/// With the default diff only layout, the diff image is a copy of the latest image with every
+/// mismatched pixel highlighted, float images being copied as 16-bit as they cannot be written as
+/// png. The other layouts are created at 8 bits per channel, the heatmap layout measuring how much
+/// each pixel differs with the options' metric.
+pub fn create_mismatch_image(
+    (original_image_holder, latest_image_holder): &(ImageHolder, ImageHolder),
+    mismatched_pixels: &[PixelCoord],
+    regions: &[MismatchRegion],
+    options: &CompareOptions,
+) -> DynamicImage {
+    create_diff_of_images(
+        &original_image_holder.image,
+        &latest_image_holder.image,
+        mismatched_pixels,
+        regions,
+        options,
+    )
+}
+
+/// Creates the diff image of every frame of the pair, as `create_mismatch_image` does, with the
+/// pixels mismatched in that frame, keeping the latest image's delays.
+pub fn create_mismatch_frames(
+    (original_image_holder, latest_image_holder): &(ImageHolder, ImageHolder),
+    frame_mismatched_pixels: &[Vec<PixelCoord>],
+    options: &CompareOptions,
+) -> Vec<AnimationFrame> {
+    let original_frames = original_image_holder.get_frames();
+
+    latest_image_holder
+        .get_frames()
+        .iter()
+        .enumerate()
+        .map(|(index, frame)| {
+            // latest frames past the original's last frame are only compared by their count
+            let original_image = original_frames
+                .get(index)
+                .map_or(&frame.image, |original_frame| &original_frame.image);
+            let mismatched_pixels = frame_mismatched_pixels
+                .get(index)
+                .map_or(&[][..], |pixels| pixels.as_slice());
+            // each frame's regions are only needed to outline them
+            let regions = match options.get_diff().has_outlines() {
+                true => get_mismatch_regions(mismatched_pixels),
+                false => Vec::new(),
+            };
+
+            AnimationFrame::new(
+                create_diff_of_images(
+                    original_image,
+                    &frame.image,
+                    mismatched_pixels,
+                    &regions,
+                    options,
+                ),
+                frame.delay_ms,
+            )
+        })
+        .collect()
+}
+
+/// Creates the frames of a blink gif of the pair, the original image then the latest image, each
+/// with every region of mismatched pixels outlined in the highlight colour, at 8 bits per channel.
+/// Animated pairs use their first frames.
+pub fn create_blink_frames(
+    (original_image_holder, latest_image_holder): &(ImageHolder, ImageHolder),
+    regions: &[MismatchRegion],
+    options: &CompareOptions,
+) -> Vec<AnimationFrame> {
+    let HighlightColour([red, green, blue]) = options.get_diff().get_highlight();
+    let highlight = Rgba([red, green, blue, 255]);
+
+    [&original_image_holder.image, &latest_image_holder.image]
+        .into_iter()
+        .map(|image| {
+            let mut blink_image = DynamicImage::ImageRgba8(image.to_rgba8());
+            for region in regions.iter() {
+                draw_outline(&mut blink_image, region, highlight);
+            }
+            AnimationFrame::new(blink_image, BLINK_DELAY_MS)
+        })
+        .collect()
+}
+
+/// Returns the regions of mismatched pixels that crops are created for, the largest first, up to
+/// a limit so that pairs with scattered mismatched pixels do not create a crop for every one.
+pub fn get_crop_regions(regions: &[MismatchRegion]) -> Vec<MismatchRegion> {
+    let mut regions = regions.to_vec();
+    regions.sort_by_key(|region| std::cmp::Reverse(region.get_area()));
+    regions.truncate(MAX_CROP_REGIONS);
+
+    regions
+}
+
+/// Crops the region, with a few pixels of context around it, out of the image and scales it up
+/// with nearest neighbour filtering, so that small changes stay sharp, by a whole number of times
+/// until its longest side is close to 256 pixels.
+pub fn create_crop_thumbnail(image: &DynamicImage, region: &MismatchRegion) -> DynamicImage {
+    let left = region.x.saturating_sub(CROP_PADDING);
+    let top = region.y.saturating_sub(CROP_PADDING);
+    let right = (region.x + region.width + CROP_PADDING).min(image.width());
+    let bottom = (region.y + region.height + CROP_PADDING).min(image.height());
+    let (width, height) = (right - left, bottom - top);
+    let scale = (CROP_TARGET_SIZE / width.max(height).max(1)).clamp(1, MAX_CROP_SCALE);
+
+    image.crop_imm(left, top, width, height).resize_exact(
+        width * scale,
+        height * scale,
+        imageops::FilterType::Nearest,
+    )
+}
+
+/// Returns the bounding box of each region of touching mismatched pixels, including diagonally
+/// touching, ordered by where the region's first pixel is found.
+pub fn get_mismatch_regions(mismatched_pixels: &[PixelCoord]) -> Vec<MismatchRegion> {
+    let mut unvisited = PixelGrid::new(mismatched_pixels);
+    let mut regions = Vec::new();
+
+    for start in mismatched_pixels.iter() {
+        if !unvisited.remove(start) {
+            continue;
+        }
+
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (start.x, start.y, start.x, start.y);
+        let mut to_visit = vec![*start];
+        while let Some(pixel_coord) = to_visit.pop() {
+            min_x = min_x.min(pixel_coord.x);
+            min_y = min_y.min(pixel_coord.y);
+            max_x = max_x.max(pixel_coord.x);
+            max_y = max_y.max(pixel_coord.y);
+
+            for y in pixel_coord.y.saturating_sub(1)..=pixel_coord.y.saturating_add(1) {
+                for x in pixel_coord.x.saturating_sub(1)..=pixel_coord.x.saturating_add(1) {
+                    let neighbour = PixelCoord::new(x, y);
+                    if unvisited.remove(&neighbour) {
+                        to_visit.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        regions.push(MismatchRegion {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x + 1,
+            height: max_y - min_y + 1,
+        });
+    }
+
+    regions
+}
+
+/// The mismatched pixels not yet visited while finding regions, held as a grid covering their
+/// bounding box, which is far quicker to look pixels up in than a set when most pixels mismatch.
+struct PixelGrid {
+    left: u32,
+    top: u32,
+    width: u32,
+    height: u32,
+    cells: Vec<bool>,
+}
+
+impl PixelGrid {
+    fn new(pixel_coords: &[PixelCoord]) -> Self {
+        let (left, top, right, bottom) = pixel_coords.iter().fold(
+            (u32::MAX, u32::MAX, 0, 0),
+            |(left, top, right, bottom), pixel_coord| {
+                (
+                    left.min(pixel_coord.x),
+                    top.min(pixel_coord.y),
+                    right.max(pixel_coord.x),
+                    bottom.max(pixel_coord.y),
+                )
+            },
+        );
+        let (width, height) = match pixel_coords.is_empty() {
+            true => (0, 0),
+            false => (right - left + 1, bottom - top + 1),
+        };
+        let mut grid = PixelGrid {
+            left,
+            top,
+            width,
+            height,
+            cells: vec![false; width as usize * height as usize],
+        };
+
+        for pixel_coord in pixel_coords.iter() {
+            if let Some(index) = grid.get_index(pixel_coord) {
+                grid.cells[index] = true;
+            }
+        }
+
+        grid
+    }
+
+    fn get_index(&self, pixel_coord: &PixelCoord) -> Option<usize> {
+        let x = pixel_coord
+            .x
+            .checked_sub(self.left)
+            .filter(|x| *x < self.width)?;
+        let y = pixel_coord
+            .y
+            .checked_sub(self.top)
+            .filter(|y| *y < self.height)?;
+
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    /// Removes the pixel from the grid, returning whether it was there.
+    fn remove(&mut self, pixel_coord: &PixelCoord) -> bool {
+        match self.get_index(pixel_coord) {
+            Some(index) => std::mem::replace(&mut self.cells[index], false),
+            None => false,
+        }
+    }
+}
+
+fn create_diff_of_images(
+    original_image: &DynamicImage,
+    latest_image: &DynamicImage,
+    mismatched_pixels: &[PixelCoord],
+    regions: &[MismatchRegion],
+    options: &CompareOptions,
+) -> DynamicImage {
+    let diff_options = options.get_diff();
+    let HighlightColour([red, green, blue]) = diff_options.get_highlight();
+    let highlight = Rgba([red, green, blue, 255]);
+
+    let mut diff_image = match diff_options.get_layout() {
+        DiffLayout::Overlay => create_overlay_image(original_image, latest_image),
+        DiffLayout::Heatmap => create_heatmap_image(original_image, latest_image, options),
+        DiffLayout::DiffOnly | DiffLayout::Triptych => {
+            // the highlight is coloured, so is drawn over an rgba copy rather than, say, a grey one
+            let mut diff_image = match latest_image {
+                DynamicImage::ImageLuma8(_)
+                | DynamicImage::ImageLumaA8(_)
+                | DynamicImage::ImageRgb8(_)
+                | DynamicImage::ImageRgba8(_) => DynamicImage::ImageRgba8(latest_image.to_rgba8()),
+                _ => DynamicImage::ImageRgba16(latest_image.to_rgba16()),
+            };
+            if diff_options.get_fade() > 0_f32 {
+                fade_image(&mut diff_image, diff_options.get_fade());
+            }
+            for pixel_coord in mismatched_pixels.iter() {
+                diff_image.put_pixel(pixel_coord.x, pixel_coord.y, highlight);
+            }
+            diff_image
+        }
+    };
+
+    if diff_options.has_outlines() {
+        for region in regions.iter() {
+            draw_outline(&mut diff_image, region, highlight);
+        }
+    }
+
+    match diff_options.get_layout() {
+        DiffLayout::Triptych => {
+            let (width, height) = latest_image.dimensions();
+            let mut triptych = RgbaImage::new(width * 3, height);
+            for (index, image) in [original_image, &diff_image, latest_image]
+                .into_iter()
+                .enumerate()
+            {
+                imageops::replace(
+                    &mut triptych,
+                    &image.to_rgba8(),
+                    (width * index as u32).into(),
+                    0,
+                );
+            }
+            DynamicImage::ImageRgba8(triptych)
+        }
+        DiffLayout::Heatmap => add_heatmap_legend(&diff_image, options),
+        DiffLayout::DiffOnly | DiffLayout::Overlay => diff_image,
+    }
+}
+
+fn create_overlay_image(
+    original_image: &DynamicImage,
+    latest_image: &DynamicImage,
+) -> DynamicImage {
+    let original_luma = original_image.to_luma8();
+    let latest_luma = latest_image.to_luma8();
+
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        latest_image.width(),
+        latest_image.height(),
+        |x, y| {
+            Rgba([
+                original_luma.get_pixel(x, y).0[0],
+                latest_luma.get_pixel(x, y).0[0],
+                0,
+                255,
+            ])
+        },
+    ))
+}
+
+/// Fades an rgba image towards white in place, leaving any other image as it is.
+fn fade_image(image: &mut DynamicImage, fade: f32) {
+    let fraction = fade.clamp(0_f32, 100_f32) / 100_f32;
+    let fade_channel = |channel: f32, max: f32| (channel + (max - channel) * fraction).round();
+
+    match image {
+        DynamicImage::ImageRgba8(buffer) => {
+            for pixel in buffer.pixels_mut() {
+                pixel.apply_without_alpha(|channel| fade_channel(channel as f32, 255_f32) as u8);
+            }
+        }
+        DynamicImage::ImageRgba16(buffer) => {
+            for pixel in buffer.pixels_mut() {
+                pixel.apply_without_alpha(|channel| {
+                    fade_channel(channel as f32, u16::MAX as f32) as u16
+                });
+            }
+        }
+        _ => {}
+    }
+}
 
-    difference <= tolerance
+/// Draws a one pixel outline just outside the region, clipped to the image.
+fn draw_outline(image: &mut DynamicImage, region: &MismatchRegion, colour: Rgba<u8>) {
+    let left = region.x as i64 - 1;
+    let top = region.y as i64 - 1;
+    let right = region.x as i64 + region.width as i64;
+    let bottom = region.y as i64 + region.height as i64;
+    let (width, height) = (image.width() as i64, image.height() as i64);
+
+    let mut put_pixel = |x: i64, y: i64| {
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            image.put_pixel(x as u32, y as u32, colour);
+        }
+    };
+
+    for x in left..=right {
+        put_pixel(x, top);
+        put_pixel(x, bottom);
+    }
+    for y in top..=bottom {
+        put_pixel(left, y);
+        put_pixel(right, y);
+    }
 }
 
 #[cfg(test)]
@@ -98,7 +503,7 @@ mod tests {
             use lab::Lab;
 
             use crate::{
-                models::PixelCoord,
+                models::{CompareOptions, PixelCoord},
                 test_utils::image::create_dynamic_image,
                 utils::image::{
                     is_pixel_for_images_matching, tests::test_helpers::create_image_holders,
@@ -132,7 +537,12 @@ mod tests {
                 assert!(difference > TOLERANCE);
                 assert_eq!(
                     EXPECTED_RESULT,
-                    is_pixel_for_images_matching(&images, &pixel_coord, TOLERANCE)
+                    is_pixel_for_images_matching(
+                        &images.0.image,
+                        &images.1.image,
+                        &pixel_coord,
+                        &CompareOptions::new(TOLERANCE)
+                    )
                 );
             }
         }
@@ -142,7 +552,7 @@ mod tests {
             use lab::Lab;
 
             use crate::{
-                models::PixelCoord,
+                models::{CompareOptions, PixelCoord},
                 test_utils::image::create_dynamic_image,
                 utils::image::{
                     is_pixel_for_images_matching, tests::test_helpers::create_image_holders,
@@ -171,7 +581,12 @@ mod tests {
                 assert_eq!(0_f32, difference);
                 assert_eq!(
                     EXPECTED_RESULT,
-                    is_pixel_for_images_matching(&images, &pixel_coord, 5_f32)
+                    is_pixel_for_images_matching(
+                        &images.0.image,
+                        &images.1.image,
+                        &pixel_coord,
+                        &CompareOptions::new(5_f32)
+                    )
                 )
             }
 
@@ -198,12 +613,351 @@ mod tests {
                 assert!(difference < TOLERANCE);
                 assert_eq!(
                     EXPECTED_RESULT,
-                    is_pixel_for_images_matching(&images, &pixel_coord, TOLERANCE)
+                    is_pixel_for_images_matching(
+                        &images.0.image,
+                        &images.1.image,
+                        &pixel_coord,
+                        &CompareOptions::new(TOLERANCE)
+                    )
                 );
             }
         }
     }
 
+    mod create_mismatch_image {
+        mod returns_image {
+            use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+
+            use crate::{
+                models::{CompareOptions, DiffLayout, DiffOptions, HighlightColour, PixelCoord},
+                test_utils::image::create_dynamic_image,
+                utils::image::{
+                    create_mismatch_image, get_mismatch_regions,
+                    tests::test_helpers::create_image_holders,
+                },
+            };
+
+            #[test]
+            fn when_there_are_mismatched_pixels_they_are_highlighted_on_the_latest_image() {
+                let image_one = create_dynamic_image(4, 4);
+                let image_two = create_dynamic_image(4, 4);
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[PixelCoord::new(1, 2)],
+                    &[],
+                    &CompareOptions::default(),
+                );
+
+                assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(1, 2));
+                assert_eq!(images.1.image.get_pixel(2, 1), result.get_pixel(2, 1));
+            }
+
+            #[test]
+            fn when_there_are_no_mismatched_pixels_it_matches_the_latest_image() {
+                let image_one = create_dynamic_image(4, 4);
+                let image_two = create_dynamic_image(4, 4);
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(&images, &[], &[], &CompareOptions::default());
+
+                assert_eq!(images.1.image, result);
+            }
+
+            #[test]
+            fn when_the_latest_image_is_grey_the_highlight_keeps_its_colour() {
+                let image_one = DynamicImage::ImageLuma8(create_dynamic_image(4, 4).to_luma8());
+                let image_two = DynamicImage::ImageLuma8(create_dynamic_image(4, 4).to_luma8());
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[PixelCoord::new(1, 2)],
+                    &[],
+                    &CompareOptions::default()
+                        .with_diff(DiffOptions::default().with_layout(DiffLayout::Triptych)),
+                );
+
+                assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(5, 2));
+            }
+
+            #[test]
+            fn when_set_the_highlight_colour_and_fade_are_used() {
+                let image_one = create_dynamic_image(4, 4);
+                let image_two = create_dynamic_image(4, 4);
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[PixelCoord::new(1, 2)],
+                    &[],
+                    &CompareOptions::default().with_diff(
+                        DiffOptions::default()
+                            .with_highlight(HighlightColour([0, 255, 255]))
+                            .with_fade(50_f32),
+                    ),
+                );
+
+                assert_eq!(Rgba([0, 255, 255, 255]), result.get_pixel(1, 2));
+                assert_eq!(Rgba([129, 128, 255, 255]), result.get_pixel(2, 0));
+            }
+
+            #[test]
+            fn when_outlines_are_set_each_region_is_outlined() {
+                let image_one = create_dynamic_image(6, 6);
+                let image_two = create_dynamic_image(6, 6);
+                let images = create_image_holders(image_one, image_two);
+
+                let mismatched_pixels = [PixelCoord::new(2, 2), PixelCoord::new(3, 3)];
+
+                let result = create_mismatch_image(
+                    &images,
+                    &mismatched_pixels,
+                    &get_mismatch_regions(&mismatched_pixels),
+                    &CompareOptions::default()
+                        .with_diff(DiffOptions::default().with_outlines(true)),
+                );
+
+                for (x, y) in [(1, 1), (4, 1), (1, 4), (4, 4), (2, 1), (1, 3)] {
+                    assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(x, y), "({x}, {y})");
+                }
+                assert_eq!(images.1.image.get_pixel(3, 2), result.get_pixel(3, 2));
+                assert_eq!(images.1.image.get_pixel(0, 0), result.get_pixel(0, 0));
+            }
+
+            #[test]
+            fn when_the_layout_is_triptych_the_pair_sits_either_side_of_the_diff() {
+                let image_one = create_dynamic_image(4, 3);
+                let mut image_two = create_dynamic_image(4, 3);
+                image_two
+                    .as_mut_rgba8()
+                    .unwrap()
+                    .put_pixel(1, 2, Rgba([9, 9, 9, 255]));
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[PixelCoord::new(1, 2)],
+                    &[],
+                    &CompareOptions::default()
+                        .with_diff(DiffOptions::default().with_layout(DiffLayout::Triptych)),
+                );
+
+                assert_eq!((12, 3), result.dimensions());
+                assert_eq!(images.0.image.get_pixel(1, 2), result.get_pixel(1, 2));
+                assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(5, 2));
+                assert_eq!(Rgba([9, 9, 9, 255]), result.get_pixel(9, 2));
+            }
+
+            #[test]
+            fn when_the_layout_is_heatmap_a_legend_is_added_beneath_it() {
+                let image_one = create_dynamic_image(40, 4);
+                let mut image_two = create_dynamic_image(40, 4);
+                image_two
+                    .as_mut_rgba8()
+                    .unwrap()
+                    .put_pixel(1, 2, Rgba([255, 0, 0, 255]));
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[PixelCoord::new(1, 2)],
+                    &[],
+                    &CompareOptions::default()
+                        .with_diff(DiffOptions::default().with_layout(DiffLayout::Heatmap)),
+                );
+
+                assert_eq!((40, 32), result.dimensions());
+                assert_eq!(Rgba([255, 0, 0, 255]), result.get_pixel(1, 2));
+                let grey = images.1.image.to_luma8().get_pixel(0, 2).0[0] / 3;
+                assert_eq!(Rgba([grey, grey, grey, 255]), result.get_pixel(0, 2));
+            }
+
+            #[test]
+            fn when_the_layout_is_overlay_the_original_is_red_and_the_latest_green() {
+                let image_one = create_dynamic_image(4, 4);
+                let image_two = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+                    4,
+                    4,
+                    Rgba([255, 255, 255, 255]),
+                ));
+                let images = create_image_holders(image_one, image_two);
+
+                let result = create_mismatch_image(
+                    &images,
+                    &[],
+                    &[],
+                    &CompareOptions::default()
+                        .with_diff(DiffOptions::default().with_layout(DiffLayout::Overlay)),
+                );
+
+                let original_luma = images.0.image.to_luma8().get_pixel(2, 1).0[0];
+                assert_eq!(Rgba([original_luma, 255, 0, 255]), result.get_pixel(2, 1));
+            }
+        }
+    }
+
+    mod create_blink_frames {
+        use image::{GenericImageView, Rgba};
+
+        use crate::{
+            models::{CompareOptions, PixelCoord},
+            test_utils::image::create_dynamic_image,
+            utils::image::{
+                create_blink_frames, get_mismatch_regions,
+                tests::test_helpers::create_image_holders,
+            },
+        };
+
+        #[test]
+        fn returns_the_original_then_latest_image_with_regions_outlined() {
+            let image_one = create_dynamic_image(6, 6);
+            let mut image_two = create_dynamic_image(6, 6);
+            image_two
+                .as_mut_rgba8()
+                .unwrap()
+                .put_pixel(3, 3, Rgba([9, 9, 9, 255]));
+            let images = create_image_holders(image_one, image_two);
+
+            let result = create_blink_frames(
+                &images,
+                &get_mismatch_regions(&[PixelCoord::new(3, 3)]),
+                &CompareOptions::default(),
+            );
+
+            assert_eq!(2, result.len());
+            assert_eq!(
+                images.0.image.get_pixel(3, 3),
+                result[0].image.get_pixel(3, 3)
+            );
+            assert_eq!(Rgba([9, 9, 9, 255]), result[1].image.get_pixel(3, 3));
+            for frame in result.iter() {
+                assert_eq!(Rgba([255, 0, 0, 255]), frame.image.get_pixel(2, 2));
+                assert_eq!(images.0.image.get_pixel(0, 0), frame.image.get_pixel(0, 0));
+                assert_eq!(500, frame.delay_ms);
+            }
+        }
+    }
+
+    mod get_crop_regions {
+        use crate::{
+            models::PixelCoord,
+            utils::image::{get_crop_regions, get_mismatch_regions},
+        };
+
+        #[test]
+        fn returns_the_largest_regions_first_up_to_the_limit() {
+            let mut mismatched_pixels: Vec<PixelCoord> =
+                (0..30).map(|index| PixelCoord::new(index * 3, 0)).collect();
+            mismatched_pixels.push(PixelCoord::new(90, 1));
+            mismatched_pixels.push(PixelCoord::new(91, 2));
+
+            let result = get_crop_regions(&get_mismatch_regions(&mismatched_pixels));
+
+            assert_eq!(20, result.len());
+            assert_eq!(
+                (90, 1, 2, 2),
+                (result[0].x, result[0].y, result[0].width, result[0].height)
+            );
+        }
+    }
+
+    mod create_crop_thumbnail {
+        use image::{GenericImageView, Rgba};
+
+        use crate::{
+            models::MismatchRegion, test_utils::image::create_dynamic_image,
+            utils::image::create_crop_thumbnail,
+        };
+
+        #[test]
+        fn crops_the_padded_region_and_scales_it_up_with_nearest_neighbour() {
+            let mut image = create_dynamic_image(100, 100);
+            image
+                .as_mut_rgba8()
+                .unwrap()
+                .put_pixel(50, 50, Rgba([9, 9, 9, 255]));
+
+            let result = create_crop_thumbnail(
+                &image,
+                &MismatchRegion {
+                    x: 50,
+                    y: 50,
+                    width: 1,
+                    height: 1,
+                },
+            );
+
+            // 8 pixels either side of the region, scaled up 15 times to be close to 256 pixels
+            assert_eq!((255, 255), result.dimensions());
+            assert_eq!(image.get_pixel(42, 42), result.get_pixel(0, 0));
+            for (x, y) in [(120, 120), (134, 134)] {
+                assert_eq!(Rgba([9, 9, 9, 255]), result.get_pixel(x, y));
+            }
+        }
+
+        #[test]
+        fn clamps_the_padding_to_the_image() {
+            let image = create_dynamic_image(20, 20);
+
+            let result = create_crop_thumbnail(
+                &image,
+                &MismatchRegion {
+                    x: 2,
+                    y: 0,
+                    width: 200,
+                    height: 4,
+                },
+            );
+
+            assert_eq!((240, 144), result.dimensions());
+        }
+    }
+
+    mod get_mismatch_regions {
+        use crate::{
+            models::{MismatchRegion, PixelCoord},
+            utils::image::get_mismatch_regions,
+        };
+
+        #[test]
+        fn returns_the_bounding_box_of_each_region_of_touching_pixels() {
+            let mismatched_pixels = [
+                PixelCoord::new(1, 1),
+                PixelCoord::new(2, 2),
+                PixelCoord::new(3, 1),
+                PixelCoord::new(8, 5),
+                PixelCoord::new(8, 6),
+            ];
+
+            let result = get_mismatch_regions(&mismatched_pixels);
+
+            assert_eq!(
+                vec![
+                    MismatchRegion {
+                        x: 1,
+                        y: 1,
+                        width: 3,
+                        height: 2
+                    },
+                    MismatchRegion {
+                        x: 8,
+                        y: 5,
+                        width: 1,
+                        height: 2
+                    },
+                ],
+                result
+            );
+        }
+
+        #[test]
+        fn returns_no_regions_when_there_are_no_mismatched_pixels() {
+            assert!(get_mismatch_regions(&[]).is_empty());
+        }
+    }
+
     mod test_helpers {
         use image::DynamicImage;
 
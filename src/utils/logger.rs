@@ -1,5 +1,5 @@
 use env_logger::Builder;
-use log::{debug, error, info, LevelFilter};
+use log::{debug, error, info, warn, LevelFilter};
 
 pub struct Logger {}
 
@@ -17,6 +17,10 @@ impl Logger {
         error!("{message}");
     }
 
+    pub fn warn(message: String) {
+        warn!("{message}");
+    }
+
     pub fn info(message: String) {
         info!("{message}");
     }
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use crate::models::RunResults;
+
+use super::file_paths::get_relative_link;
+
+/// Renders a short summary of the run results as markdown, such as for a pull request comment,
+/// with the diff images linked relative to the directory the summary will be written to.
+pub fn render_markdown_summary(run_results: &RunResults, summary_location: &str) -> String {
+    let summary_dir = Path::new(summary_location)
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let link = |location: &str| {
+        get_relative_link(&summary_dir, location)
+            .replace(' ', "%20")
+            .replace('(', "%28")
+            .replace(')', "%29")
+    };
+
+    let mismatched_pairs: Vec<_> = run_results.get_mismatched_pairs().collect();
+
+    let mut markdown = String::from("## Image visual comparison\n\n");
+    markdown.push_str(&format!(
+        "Pairs: {}, mismatched: {}, new: {}, missing: {}\n",
+        run_results.pairs.len(),
+        mismatched_pairs.len(),
+        run_results.new_images.len(),
+        run_results.missing_images.len()
+    ));
+
+    if !mismatched_pairs.is_empty() {
+        markdown.push_str("\n### Mismatched pairs\n\n");
+        markdown.push_str("| Image | Mismatched | Regions | Diff |\n| --- | ---: | ---: | --- |\n");
+        for pair in mismatched_pairs.iter() {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                escape_markdown(&pair.latest),
+                pair.mismatch_percentage
+                    .map_or("-".to_string(), |percentage| format!("{percentage:.2}%")),
+                pair.region_count
+                    .map_or("-".to_string(), |region_count| region_count.to_string()),
+                pair.diff
+                    .as_deref()
+                    .map_or("-".to_string(), |diff| format!("[diff]({})", link(diff))),
+            ));
+        }
+    }
+
+    for (heading, images) in [
+        ("New images", &run_results.new_images),
+        ("Missing images", &run_results.missing_images),
+    ] {
+        if images.is_empty() {
+            continue;
+        }
+        markdown.push_str(&format!("\n### {heading}\n\n"));
+        for image in images.iter() {
+            markdown.push_str(&format!("- {}\n", escape_markdown(image)));
+        }
+    }
+
+    markdown
+}
+
+/// Escapes the characters of the value that markdown would otherwise treat as formatting, or as
+/// the end of a table cell.
+fn escape_markdown(value: &str) -> String {
+    value.chars().fold(
+        String::with_capacity(value.len()),
+        |mut escaped, character| {
+            if "\\`*_[]<>|#".contains(character) {
+                escaped.push('\\');
+            }
+            escaped.push(character);
+            escaped
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    mod render_markdown_summary {
+        use crate::{
+            models::{PairResult, PairStatus, RunResults},
+            test_utils::results::create_pair_result,
+            utils::markdown::render_markdown_summary,
+        };
+
+        fn create_pair(name: &str, status: PairStatus) -> PairResult {
+            PairResult {
+                mismatch_percentage: Some(1.5),
+                region_count: Some(2),
+                diff: Some(format!("images/mismatched/{name}")),
+                ..create_pair_result(
+                    &format!("images/original/{name}"),
+                    &format!("images/latest/{name}"),
+                    status,
+                )
+            }
+        }
+
+        #[test]
+        fn includes_totals_mismatched_pairs_and_unpaired_images() {
+            let run_results = RunResults {
+                pairs: vec![
+                    create_pair("a_b.png", PairStatus::Mismatched),
+                    create_pair("c.png", PairStatus::Matched),
+                ],
+                new_images: vec!["images/latest/new.png".to_string()],
+                missing_images: vec![],
+            };
+
+            let markdown = render_markdown_summary(&run_results, "images/summary.md");
+
+            assert!(markdown.contains("Pairs: 2, mismatched: 1, new: 1, missing: 0\n"));
+            assert!(markdown.contains(
+                "| images/latest/a\\_b.png | 1.50% | 2 | [diff](mismatched/a_b.png) |\n"
+            ));
+            assert!(!markdown.contains("c.png"));
+            assert!(markdown.contains("### New images\n\n- images/latest/new.png\n"));
+            assert!(!markdown.contains("### Missing images"));
+        }
+
+        #[test]
+        fn leaves_out_the_table_when_every_pair_matches() {
+            let run_results = RunResults {
+                pairs: vec![create_pair("c.png", PairStatus::Matched)],
+                ..RunResults::default()
+            };
+
+            let markdown = render_markdown_summary(&run_results, "summary.md");
+
+            assert_eq!(
+                "## Image visual comparison\n\nPairs: 1, mismatched: 0, new: 0, missing: 0\n",
+                markdown
+            );
+        }
+    }
+}
@@ -1,5 +1,10 @@
 pub mod colour;
+pub mod colour_profile;
 pub mod file_paths;
 pub mod file_system;
+pub mod globs;
+pub mod heatmap;
 pub mod image;
 pub mod logger;
+pub mod markdown;
+pub mod report;
@@ -0,0 +1,298 @@
+use std::path::Path;
+
+use crate::models::{PairResult, PairStatus, ReviewDecision, RunResults};
+
+use super::file_paths::{get_file_location, get_relative_link};
+
+/// Renders the run results as a standalone html page, with images linked relative to the
+/// directory the report will be written to. A page of a multi-page image is linked as its whole
+/// file.
+pub fn render_html_report(run_results: &RunResults, report_location: &str) -> String {
+    let report_dir = Path::new(report_location)
+        .parent()
+        .map(|parent| parent.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let link = |location: &str| {
+        encode_url_value(&get_relative_link(&report_dir, get_file_location(location)))
+    };
+
+    render_html_page(run_results, &link, None)
+}
+
+/// Renders the run results as the review server's page, with images linked via the server and
+/// approve/reject buttons, carrying the server's token, for each mismatched pair that has not been
+/// reviewed yet.
+pub fn render_review_page(run_results: &RunResults, review_token: &str) -> String {
+    let link = |location: &str| format!("/image?location={}", encode_url_value(location));
+
+    render_html_page(run_results, &link, Some(review_token))
+}
+
+fn render_html_page(
+    run_results: &RunResults,
+    get_link: &impl Fn(&str) -> String,
+    review_token: Option<&str>,
+) -> String {
+    let link = |location: &str| escape_html(&get_link(location));
+
+    let mismatched_count = run_results.get_mismatched_pairs().count();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Image visual comparison report</title>\n");
+    html.push_str("<style>body{font-family:sans-serif}img{max-width:30vw;border:1px solid #ccc}.mismatched h2{color:#c00}.matched h2{color:#080}</style>\n");
+    html.push_str("</head>\n<body>\n<h1>Image visual comparison report</h1>\n");
+    html.push_str(&format!(
+        "<p>Pairs: {}, mismatched: {}, new: {}, missing: {}</p>\n",
+        run_results.pairs.len(),
+        mismatched_count,
+        run_results.new_images.len(),
+        run_results.missing_images.len()
+    ));
+
+    for pair in run_results.pairs.iter() {
+        html.push_str(&render_pair(pair, &link, review_token));
+    }
+
+    for (heading, images) in [
+        ("New images", &run_results.new_images),
+        ("Missing images", &run_results.missing_images),
+    ] {
+        if images.is_empty() {
+            continue;
+        }
+        html.push_str(&format!("<h2>{heading}</h2>\n<ul>\n"));
+        for image in images.iter() {
+            html.push_str(&format!(
+                "<li><a href=\"{}\">{}</a></li>\n",
+                link(image),
+                escape_html(image)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_pair(
+    pair: &PairResult,
+    link: &impl Fn(&str) -> String,
+    review_token: Option<&str>,
+) -> String {
+    let (class, status) = match pair.status {
+        PairStatus::Matched => ("matched", "matched"),
+        PairStatus::Mismatched => ("mismatched", "mismatched"),
+    };
+
+    let mut html = format!(
+        "<section class=\"{class}\">\n<h2>{}</h2>\n<p>Status: {status}, mismatched pixels: {}</p>\n",
+        escape_html(&pair.latest),
+        pair.mismatched_pixel_count
+    );
+    if let Some(animation) = &pair.animation {
+        let mismatched_frames: Vec<String> = animation
+            .frames
+            .iter()
+            .filter(|frame| {
+                frame.mismatched_pixel_count > 0 || frame.original_delay_ms != frame.latest_delay_ms
+            })
+            .map(|frame| frame.index.to_string())
+            .collect();
+        html.push_str(&format!(
+            "<p>Frames - original: {}, latest: {}, mismatched frames: {}</p>\n",
+            animation.original_frame_count,
+            animation.latest_frame_count,
+            match mismatched_frames.is_empty() {
+                true => "none".to_string(),
+                false => mismatched_frames.join(", "),
+            }
+        ));
+    }
+    if let Some(profiles) = &pair.profiles {
+        html.push_str(&format!(
+            "<p>Colour profiles - original: {}, latest: {}, converted: {}</p>\n",
+            escape_html(profiles.original.as_deref().unwrap_or("none")),
+            escape_html(profiles.latest.as_deref().unwrap_or("none")),
+            match profiles.converted {
+                true => "yes",
+                false => "no",
+            }
+        ));
+    }
+    html.push_str(&format!(
+        "<img alt=\"original\" src=\"{}\">\n<img alt=\"latest\" src=\"{}\">\n",
+        link(&pair.original),
+        link(&pair.latest)
+    ));
+    if let Some(diff) = &pair.diff {
+        html.push_str(&format!("<img alt=\"diff\" src=\"{}\">\n", link(diff)));
+    }
+    if let Some(blink) = &pair.blink {
+        html.push_str(&format!("<img alt=\"blink\" src=\"{}\">\n", link(blink)));
+    }
+    for (index, crop) in pair.crops.iter().enumerate() {
+        html.push_str(&format!(
+            "<div class=\"region\">\n<p>Region {} - x: {}, y: {}, width: {}, height: {}</p>\n",
+            index + 1,
+            crop.region.x,
+            crop.region.y,
+            crop.region.width,
+            crop.region.height
+        ));
+        for (image_name, location) in [
+            ("original", &crop.original),
+            ("latest", &crop.latest),
+            ("diff", &crop.diff),
+        ] {
+            html.push_str(&format!(
+                "<img alt=\"region {} {image_name}\" src=\"{}\">\n",
+                index + 1,
+                link(location)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    match pair.review {
+        Some(ReviewDecision::Approved) => html.push_str("<p>Review: approved</p>\n"),
+        Some(ReviewDecision::Rejected) => html.push_str("<p>Review: rejected</p>\n"),
+        None => match review_token {
+            Some(review_token) if pair.status == PairStatus::Mismatched => {
+                for action in ["approve", "reject"] {
+                    html.push_str(&format!(
+                        "<form method=\"post\" action=\"/{action}\"><input type=\"hidden\" name=\"latest\" value=\"{}\"><input type=\"hidden\" name=\"token\" value=\"{}\"><button type=\"submit\">{action}</button></form>\n",
+                        escape_html(&pair.latest),
+                        escape_html(review_token)
+                    ));
+                }
+            }
+            _ => {}
+        },
+    }
+    html.push_str("</section>\n");
+
+    html
+}
+
+pub fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encodes the value for use within a url's path or query string, leaving its slashes as
+/// they are.
+pub fn encode_url_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    mod render_html_report {
+        use crate::{
+            models::{MismatchRegion, PairResult, PairStatus, RegionCrop, RunResults},
+            test_utils::results::create_pair_result,
+            utils::report::render_html_report,
+        };
+
+        #[test]
+        fn includes_each_pair_with_relative_image_links_and_unpaired_images() {
+            let run_results = RunResults {
+                pairs: vec![PairResult {
+                    mismatched_pixel_count: 4,
+                    diff: Some("images/mismatched/a.png".to_string()),
+                    blink: Some("images/mismatched/a.blink.gif".to_string()),
+                    crops: vec![RegionCrop {
+                        region: MismatchRegion {
+                            x: 1,
+                            y: 2,
+                            width: 3,
+                            height: 4,
+                        },
+                        original: "images/mismatched/a.region1.original.png".to_string(),
+                        latest: "images/mismatched/a.region1.latest.png".to_string(),
+                        diff: "images/mismatched/a.region1.diff.png".to_string(),
+                    }],
+                    ..create_pair_result(
+                        "images/original/a.png",
+                        "images/latest/a.png",
+                        PairStatus::Mismatched,
+                    )
+                }],
+                new_images: vec![
+                    "images/latest/<new>.png".to_string(),
+                    "images/latest/50% #1?.png".to_string(),
+                ],
+                missing_images: vec![],
+            };
+
+            let html = render_html_report(&run_results, "images/mismatched/report.html");
+
+            assert!(html.contains("<p>Pairs: 1, mismatched: 1, new: 2, missing: 0</p>"));
+            assert!(html.contains("<img alt=\"original\" src=\"../original/a.png\">"));
+            assert!(html.contains("<img alt=\"diff\" src=\"a.png\">"));
+            assert!(html.contains("<img alt=\"blink\" src=\"a.blink.gif\">"));
+            assert!(html.contains("<p>Region 1 - x: 1, y: 2, width: 3, height: 4</p>"));
+            assert!(html.contains("<img alt=\"region 1 diff\" src=\"a.region1.diff.png\">"));
+            assert!(html.contains("mismatched pixels: 4"));
+            assert!(html.contains("<h2>New images</h2>"));
+            assert!(html.contains("images/latest/&lt;new&gt;.png"));
+            assert!(html.contains("href=\"../latest/50%25%20%231%3F.png\""));
+            assert!(!html.contains("<h2>Missing images</h2>"));
+        }
+    }
+
+    mod render_review_page {
+        use crate::{
+            models::{PairResult, PairStatus, ReviewDecision, RunResults},
+            test_utils::results::create_pair_result,
+            utils::report::render_review_page,
+        };
+
+        #[test]
+        fn includes_review_actions_only_for_unreviewed_mismatched_pairs() {
+            let pair =
+                |name: &str, status: PairStatus, review: Option<ReviewDecision>| PairResult {
+                    review,
+                    ..create_pair_result(
+                        &format!("images/original/{name}"),
+                        &format!("images/latest/{name}"),
+                        status,
+                    )
+                };
+            let run_results = RunResults {
+                pairs: vec![
+                    pair("a b.png", PairStatus::Mismatched, None),
+                    pair(
+                        "c.png",
+                        PairStatus::Mismatched,
+                        Some(ReviewDecision::Rejected),
+                    ),
+                    pair("d.png", PairStatus::Matched, None),
+                ],
+                new_images: vec![],
+                missing_images: vec![],
+            };
+
+            let html = render_review_page(&run_results, "abc123");
+
+            assert_eq!(1, html.matches("action=\"/approve\"").count());
+            assert_eq!(1, html.matches("action=\"/reject\"").count());
+            assert!(html.contains("name=\"latest\" value=\"images/latest/a b.png\""));
+            assert_eq!(2, html.matches("name=\"token\" value=\"abc123\"").count());
+            assert!(html.contains("src=\"/image?location=images/latest/a%20b.png\""));
+            assert!(html.contains("<p>Review: rejected</p>"));
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    compare::compare_image_pair,
+    config::CompareConfig,
+    errors::{handling::create_watch_error, ivc::IVCError},
+    logger::Logger,
+    models::{PairResult, RunResults},
+    run,
+    utils::{
+        file_paths::{
+            get_file_location, get_mismatched_image_location, get_paired_original_location,
+            get_unpaired_file_paths, is_in_shard,
+        },
+        file_system::{
+            files::{get_files_for_directories, is_image_file_location},
+            images::{get_pairs_of_pages_from_file_locations, save_svg_rasters_of_pair},
+            results::{read_run_results, write_run_outputs},
+        },
+    },
+};
+
+// notification crates can send several events for a single file being written
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(250);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Runs the comparison, then watches the latest directory and re-compares only the pairs whose
+/// latest image changed, refreshing the results file and html report after each batch of changes.
+///
+/// Uses the platform's file system notifications, falling back to polling where they are not
+/// available. Only returns when the watch could not be started or the watcher stops.
+pub fn watch(config: impl Into<CompareConfig>) -> Result<(), IVCError> {
+    let config = config.into();
+    let latest_dir = config.get_latest_images_dir();
+
+    let mut run_results = match run(config.clone()) {
+        Ok(run_results) => run_results,
+        Err(err) => {
+            Logger::error(err.to_string());
+            read_run_results(&config.get_results_file_location()).unwrap_or_default()
+        }
+    };
+
+    let watched_dir = fs::canonicalize(&latest_dir)
+        .map_err(|error| create_watch_error(&latest_dir, notify::Error::io(error)))?;
+    let (sender, receiver) = mpsc::channel();
+    let _watcher = create_watcher(&watched_dir, sender)?;
+
+    Logger::info(format!(
+        "Watching for changes to latest images: '{latest_dir}'"
+    ));
+
+    while let Some(changed_locations) = receive_changed_locations(&config, &watched_dir, &receiver)
+    {
+        if changed_locations.is_empty() {
+            continue;
+        }
+
+        Logger::info(format!(
+            "Re-comparing changed latest images: '{}'",
+            changed_locations.len()
+        ));
+        refresh_changed_pairs(&config, &mut run_results, &changed_locations);
+
+        if let Err(err) = write_run_outputs(&config, &run_results) {
+            Logger::error(err.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn create_watcher(
+    watched_dir: &Path,
+    sender: Sender<notify::Result<Event>>,
+) -> Result<Box<dyn Watcher>, IVCError> {
+    let location = watched_dir.to_string_lossy();
+
+    let recommended_watcher =
+        RecommendedWatcher::new(sender.clone(), Config::default()).and_then(|mut watcher| {
+            watcher.watch(watched_dir, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+
+    match recommended_watcher {
+        Ok(watcher) => Ok(Box::new(watcher)),
+        Err(err) => {
+            Logger::warn(format!(
+                "File system notifications unavailable, polling for changes instead. Message: '{err}'"
+            ));
+
+            let mut watcher =
+                PollWatcher::new(sender, Config::default().with_poll_interval(POLL_INTERVAL))
+                    .map_err(|err| create_watch_error(&location, err))?;
+            watcher
+                .watch(watched_dir, RecursiveMode::Recursive)
+                .map_err(|err| create_watch_error(&location, err))?;
+
+            Ok(Box::new(watcher))
+        }
+    }
+}
+
+/// Waits for the next change, then collects any further changes that arrive in quick succession.
+///
+/// Returns the changed image locations, in the same form as the configured latest directory, or
+/// `None` once the watcher has stopped.
+fn receive_changed_locations(
+    config: &CompareConfig,
+    watched_dir: &Path,
+    receiver: &Receiver<notify::Result<Event>>,
+) -> Option<Vec<String>> {
+    let mut changed_locations = BTreeSet::new();
+    let mut next_event = Some(receiver.recv().ok()?);
+
+    while let Some(event) = next_event {
+        match event {
+            Ok(event) if is_content_change(&event.kind) => {
+                for path in event.paths.iter() {
+                    if let Some(location) = get_latest_location(config, watched_dir, path) {
+                        changed_locations.insert(location);
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                Logger::error(create_watch_error(&config.get_latest_images_dir(), err).to_string())
+            }
+        }
+
+        next_event = receiver.recv_timeout(DEBOUNCE_DURATION).ok();
+    }
+
+    Some(changed_locations.into_iter().collect())
+}
+
+fn is_content_change(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    )
+}
+
+fn get_latest_location(config: &CompareConfig, watched_dir: &Path, path: &Path) -> Option<String> {
+    let relative_path = path.strip_prefix(watched_dir).ok()?;
+    let latest_dir = config.get_latest_images_dir();
+    let location = PathBuf::from(&latest_dir)
+        .join(relative_path)
+        .to_string_lossy()
+        .to_string();
+
+    (is_image_file_location(config, &location)
+        && !config.is_excluded(&location, &latest_dir)
+        && is_in_shard(config, &location, &latest_dir))
+    .then_some(location)
+}
+
+/// Re-compares each changed latest image against its original, replacing any earlier result and
+/// diff for the pair, and refreshes which images are new or missing.
+pub fn refresh_changed_pairs(
+    config: &CompareConfig,
+    run_results: &mut RunResults,
+    changed_latest_locations: &[String],
+) {
+    let latest_dir = config.get_latest_images_dir();
+    let mismatched_dir = config.get_mismatched_images_dir();
+    let is_keeping_extension = config.is_keeping_extensions_in_mismatched_names();
+
+    let (original_paths, latest_paths) =
+        get_files_for_directories(config, &config.get_original_images_dir(), &latest_dir);
+    let (new_images, missing_images) =
+        get_unpaired_file_paths(config, &original_paths, &latest_paths);
+    run_results.new_images = new_images;
+    run_results.missing_images = missing_images;
+
+    let (stale_pairs, pairs) = run_results.pairs.drain(..).partition(|pair: &PairResult| {
+        let latest_file_location = get_file_location(&pair.latest);
+        changed_latest_locations
+            .iter()
+            .any(|location| location == latest_file_location)
+            || !Path::new(latest_file_location).is_file()
+    });
+    run_results.pairs = pairs;
+
+    for written_location in stale_pairs
+        .iter()
+        .flat_map(PairResult::get_written_locations)
+    {
+        let _ = fs::remove_file(written_location);
+    }
+
+    for latest_location in changed_latest_locations.iter() {
+        if !Path::new(latest_location).is_file() {
+            continue;
+        }
+
+        let original_location =
+            match get_paired_original_location(config, latest_location, &original_paths) {
+                Some(original_location) => original_location,
+                None => continue,
+            };
+        let image_pairs = match get_pairs_of_pages_from_file_locations(
+            &original_location,
+            latest_location,
+            &config.get_svg_options(),
+        ) {
+            Ok(image_pairs) => image_pairs,
+            Err(err) => {
+                Logger::error(err.to_string());
+                continue;
+            }
+        };
+
+        for image_pair in image_pairs.into_iter() {
+            let mismatched_location = get_mismatched_image_location(
+                &image_pair.1.location,
+                &latest_dir,
+                &mismatched_dir,
+                is_keeping_extension,
+            );
+            if config.is_saving_svg_rasters() {
+                if let Err(err) = save_svg_rasters_of_pair(&image_pair, &mismatched_location) {
+                    Logger::error(err.to_string());
+                }
+            }
+
+            match compare_image_pair(
+                image_pair,
+                Some(&mismatched_location),
+                &config.get_compare_options_for(latest_location),
+            ) {
+                Ok(comparison_result) => {
+                    run_results.pairs.push(PairResult::from(&comparison_result))
+                }
+                Err(err) => Logger::error(err.to_string()),
+            }
+        }
+    }
+
+    run_results.sort();
+}
+
+#[cfg(test)]
+mod tests {
+    mod receive_changed_locations {
+        use std::{path::Path, sync::mpsc};
+
+        use crate::{test_utils::config::create_config_for_test, watch::receive_changed_locations};
+
+        #[test]
+        fn when_the_watcher_has_stopped() {
+            let config = create_config_for_test("images");
+            let (sender, receiver) = mpsc::channel();
+            drop(sender);
+
+            assert_eq!(
+                None,
+                receive_changed_locations(&config, Path::new("images"), &receiver)
+            );
+        }
+    }
+
+    mod refresh_changed_pairs {
+        use std::{fs, path::Path};
+
+        use crate::{
+            config::CompareConfig,
+            models::{PairStatus, RunResults},
+            run,
+            test_utils::{
+                config::create_config_for_test,
+                files::{create_temp_dir_handler, setup_image_directories},
+                image::{change_pixel_on_img, create_dynamic_image},
+            },
+            watch::refresh_changed_pairs,
+        };
+
+        #[test]
+        fn re_compares_only_the_changed_pairs() {
+            let temp_dir_handler = create_temp_dir_handler();
+            setup_image_directories(&temp_dir_handler);
+            let config = CompareConfig::builder()
+                .directory(temp_dir_handler.get_temp_dir_path())
+                .blink(true)
+                .crops(true)
+                .build();
+            let mut run_results = run(config.clone()).unwrap();
+
+            let changed_location = temp_dir_handler.get_location_of_file_name("latest/changed.png");
+            let diff_location =
+                temp_dir_handler.get_location_of_file_name("mismatched/changed.png");
+            let blink_location =
+                temp_dir_handler.get_location_of_file_name("mismatched/changed.blink.gif");
+            let crop_location = temp_dir_handler
+                .get_location_of_file_name("mismatched/changed.region1.original.png");
+            assert!(Path::new(&diff_location).is_file());
+            assert!(Path::new(&blink_location).is_file());
+            assert!(Path::new(&crop_location).is_file());
+
+            create_dynamic_image(5, 5).save(&changed_location).unwrap();
+            refresh_changed_pairs(&config, &mut run_results, &[changed_location]);
+
+            assert_eq!(2, run_results.pairs.len());
+            assert!(run_results
+                .pairs
+                .iter()
+                .all(|pair| pair.status == PairStatus::Matched && pair.diff.is_none()));
+            assert!(!Path::new(&diff_location).exists());
+            assert!(!Path::new(&blink_location).exists());
+            assert!(!Path::new(&crop_location).exists());
+        }
+
+        #[test]
+        fn records_new_images_and_drops_pairs_whose_latest_was_removed() {
+            let temp_dir_handler = create_temp_dir_handler();
+            setup_image_directories(&temp_dir_handler);
+            let config = create_config_for_test(temp_dir_handler.get_temp_dir_path());
+            let mut run_results = RunResults::default();
+
+            let same_location = temp_dir_handler.get_location_of_file_name("latest/same.png");
+            let changed_location = temp_dir_handler.get_location_of_file_name("latest/changed.png");
+            let new_location = temp_dir_handler.get_location_of_file_name("latest/new.png");
+            let mut new_image = create_dynamic_image(5, 5);
+            change_pixel_on_img(&mut new_image, 1, 1);
+            new_image.save(&new_location).unwrap();
+            fs::remove_file(&same_location).unwrap();
+
+            refresh_changed_pairs(
+                &config,
+                &mut run_results,
+                &[
+                    same_location,
+                    changed_location.to_owned(),
+                    new_location.to_owned(),
+                ],
+            );
+
+            assert_eq!(1, run_results.pairs.len());
+            assert_eq!(changed_location, run_results.pairs[0].latest);
+            assert_eq!(PairStatus::Mismatched, run_results.pairs[0].status);
+            assert_eq!(vec![new_location], run_results.new_images);
+            assert_eq!(
+                vec![temp_dir_handler.get_location_of_file_name("original/same.png")],
+                run_results.missing_images
+            );
+        }
+    }
+}